@@ -1,13 +1,44 @@
-use std::{fs, path::PathBuf};
+use std::{error::Error, fmt, fs, io, path::PathBuf, str::FromStr};
 use serde::{Deserialize, Serialize};
 use ron::from_str;
 
+/// Format of the per-input coverage files in the traces directories
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageBackend {
+    /// drcov basic block traces, mapped onto source lines through the DWARF information of
+    /// `target_path`
+    #[default]
+    DrCov,
+    /// drcov traces of a tracer in full trace mode, which lists every executed basic block again
+    /// each time it runs, in the order of execution. Mapped like `DrCov`, they also give the
+    /// execution count of every line and the edges between consecutive blocks
+    DrCovFullTrace,
+    /// `llvm-cov export -format=text` of a target built with `-fprofile-instr-generate
+    /// -fcoverage-mapping`, with exact regions and execution counts
+    LlvmCovJson,
+    /// LCOV tracefiles (`.info`) as written by `lcov`, `geninfo` or `llvm-cov export -format=lcov`
+    Lcov,
+    /// The JSON written by `gcov --json-format` or `gcovr --json` for a target built with GCC's
+    /// `--coverage`, optionally gzip compressed
+    GcovJson,
+    /// `.sancov` PC lists written by SanitizerCoverage builds, symbolized through the DWARF
+    /// information of the target binary
+    Sancov,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct TargetConfig {
     pub target_path: String,
     pub target_source_code_path: String,
     pub target_include_filter: Vec<String>,
     pub allowed_extensions: Vec<String>,
+    #[serde(default)]
+    pub coverage_backend: CoverageBackend,
+    /// Shared objects (or further binaries) loaded by the target whose basic blocks are mapped
+    /// onto source lines as well, matched against the module paths of the traces like
+    /// `target_path`
+    #[serde(default)]
+    pub additional_modules: Vec<String>,
 }
 
 impl Default for TargetConfig {
@@ -23,8 +54,44 @@ impl TargetConfig {
             target_source_code_path: "".to_string(),
             target_include_filter: Vec::new(),
             allowed_extensions: Vec::new(),
+            coverage_backend: CoverageBackend::default(),
+            additional_modules: Vec::new(),
         }
     }
+
+    /// Paths of all modules of the target, `target_path` first
+    pub fn module_paths(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.target_path.as_str())
+            .chain(self.additional_modules.iter().map(String::as_str))
+    }
+}
+
+/// How the fuzzer names its queue entries, and thereby the trace files recorded for them
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub enum QueueNameFormat {
+    /// AFL++ built with `external/rename_seeds_afl.patch`
+    #[default]
+    PatchedAflPlusPlus,
+    /// Stock AFL++: `id:…,src:…,time:…,execs:…,op:…`
+    AflPlusPlus,
+    /// Honggfuzz inputs renamed by `external/post_process_honggfuzz_inputs.py`
+    Honggfuzz,
+    /// Output directory of Honggfuzz built with `external/rename_seeds_honggfuzz.patch`, parents
+    /// are taken from its log and initial seeds from the seed corpus directory
+    HonggfuzzOutput {
+        log_path: String,
+        seed_corpus_path: String,
+    },
+    /// libFuzzer corpus with SHA1 file names, discovery times are taken from the log of the run
+    /// and initial seeds from the seed corpus directory
+    LibFuzzer {
+        log_path: String,
+        seed_corpus_path: String,
+    },
+    /// Regular expression matched against the queue entry name. The named groups `id` (required),
+    /// `src`, `time`, `executed_on`, `execs`, `edges_found` and `orig` are picked up, an input
+    /// matching `orig` is an initial seed.
+    Regex(String),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -33,12 +100,144 @@ pub struct FuzzerConfig {
     pub traces_directory_path: String,
     pub inputs_directory_path: String,
     pub fuzzer_configuration_id: u32,
+    #[serde(default)]
+    pub queue_name_format: QueueNameFormat,
+}
+
+/// How `server extract` records the drcov trace of an input
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct TracerConfig {
+    /// `afl-qemu-trace` of an AFL++ build, looked up in `PATH` if it has no directory
+    pub qemu_trace_path: String,
+    /// `libdrcov.so` built in `qemu_mode/qemuafl/build/contrib/plugins` of the same AFL++ build
+    pub drcov_plugin_path: String,
+    /// Argument of the drcov plugin, passed as `arg=<argument>` next to the trace file name, that
+    /// makes it list every executed basic block. Needed to record `DrCovFullTrace` traces.
+    pub full_trace_plugin_argument: Option<String>,
+    /// Arguments of the target, `@@` is replaced by the path of the input. Without `@@` the input
+    /// is passed on stdin.
+    pub target_arguments: Vec<String>,
+    /// Inputs running longer are killed and get no trace
+    pub timeout_ms: u64,
+    /// Number of inputs traced at the same time, 0 for one per CPU
+    pub jobs: usize,
+}
+
+impl Default for TracerConfig {
+    fn default() -> Self {
+        TracerConfig {
+            qemu_trace_path: "afl-qemu-trace".to_string(),
+            drcov_plugin_path: "libdrcov.so".to_string(),
+            full_trace_plugin_argument: None,
+            target_arguments: vec!["@@".to_string()],
+            timeout_ms: 10000,
+            jobs: 0,
+        }
+    }
+}
+
+/// Messages the server prints while running, from none to all
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    /// Also every request
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" => Ok(LogLevel::Off),
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            _ => Err(format!(
+                "unknown log level {}, expected off, error, warn, info, debug or trace",
+                s
+            )),
+        }
+    }
+}
+
+/// How clients have to authenticate to the server
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub enum AuthConfig {
+    /// `Authorization: Bearer <token>`
+    BearerToken(String),
+    /// `Authorization: Basic …`, e.g. for browsers and `curl -u`
+    Basic { username: String, password: String },
+}
+
+/// PEM files of the certificate chain and private key the server uses to serve HTTPS
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// Server certificate first, followed by any intermediate certificates
+    pub certificate_path: String,
+    /// PKCS #8, PKCS #1 (RSA) or SEC1 (EC) private key of the certificate
+    pub private_key_path: String,
+}
+
+/// Where and how the server serves the web client, each can be overridden on the command line
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Address to listen on, e.g. `0.0.0.0` to serve other machines
+    pub bind_address: String,
+    pub port: u16,
+    /// Number of threads handling requests
+    pub workers: usize,
+    pub log_level: LogLevel,
+    /// Where the snapshot of the databases is kept, next to the configuration file
+    /// (`<configuration>.snapshot`) if not given
+    pub snapshot_path: Option<String>,
+    /// Origins the web client may be served from, `*` for any
+    pub allowed_origins: Vec<String>,
+    /// Without it, anyone who can reach the server can read the inputs and coverage
+    pub auth: Option<AuthConfig>,
+    /// Serves HTTPS instead of plain HTTP
+    pub tls: Option<TlsConfig>,
+    /// How often the traces directories are checked for new traces of a running campaign, they
+    /// are only read at startup if not given
+    pub watch_interval_ms: Option<u64>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            bind_address: "127.0.0.1".to_string(),
+            port: 8080,
+            workers: 1,
+            log_level: LogLevel::default(),
+            snapshot_path: None,
+            allowed_origins: vec![
+                "http://localhost:3000".to_string(),
+                "https://localhost:3000".to_string(),
+            ],
+            auth: None,
+            tls: None,
+            watch_interval_ms: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct UserConfig {
     pub target_info: TargetConfig,
     pub fuzzer_infos: Vec<FuzzerConfig>,
+    #[serde(default)]
+    pub tracer: TracerConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
 }
 
 impl Default for UserConfig {
@@ -52,77 +251,84 @@ impl UserConfig {
         UserConfig {
             target_info: TargetConfig::new(),
             fuzzer_infos: Vec::new(),
+            tracer: TracerConfig::default(),
+            server: ServerConfig::default(),
         }
     }
 }
 
-fn canonicalize(config_old: UserConfig) -> UserConfig {
-    let mut config_new = UserConfig::new();
-
-    config_new.target_info.target_path =
-        fs::canonicalize(PathBuf::from(&config_old.target_info.target_path))
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-    config_new.target_info.target_source_code_path = fs::canonicalize(PathBuf::from(
-        &config_old.target_info.target_source_code_path,
-    ))
-    .unwrap()
-    .to_str()
-    .unwrap()
-    .to_string();
-
-    for item in config_old.target_info.target_include_filter.iter() {
-        config_new.target_info.target_include_filter.push(
-            fs::canonicalize(PathBuf::from(item.to_string()))
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string(),
-        );
+#[derive(Debug)]
+pub enum ConfigError {
+    Io { path: String, source: io::Error },
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => write!(f, "{}: {}", path, source),
+            ConfigError::Parse(e) => write!(f, "invalid configuration: {:?}", e),
+        }
     }
+}
 
-    for item in config_old.target_info.allowed_extensions.iter() {
-        config_new
-            .target_info
-            .allowed_extensions
-            .push(item.to_string());
-    }
-
-    for fuzz_item in config_old.fuzzer_infos.iter() {
-        config_new.fuzzer_infos.push(FuzzerConfig {
-            fuzzer_configuration_id: fuzz_item.fuzzer_configuration_id,
-            fuzzer_configuration: fuzz_item.fuzzer_configuration.to_string(),
-            traces_directory_path: fs::canonicalize(PathBuf::from(
-                fuzz_item.traces_directory_path.to_string(),
-            ))
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string(),
-            inputs_directory_path: fs::canonicalize(PathBuf::from(
-                fuzz_item.inputs_directory_path.to_string(),
-            ))
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string(),
-        });
-    }
-
-    config_new
+impl Error for ConfigError {}
+
+fn canonicalize_path(path: &str) -> Result<String, ConfigError> {
+    match fs::canonicalize(PathBuf::from(path)) {
+        Ok(p) => Ok(p.to_string_lossy().into_owned()),
+        Err(source) => Err(ConfigError::Io {
+            path: path.to_string(),
+            source,
+        }),
+    }
+}
+
+fn canonicalize(mut config: UserConfig) -> Result<UserConfig, ConfigError> {
+    config.target_info.target_path = canonicalize_path(&config.target_info.target_path)?;
+    config.target_info.target_source_code_path =
+        canonicalize_path(&config.target_info.target_source_code_path)?;
+
+    for item in config.target_info.target_include_filter.iter_mut() {
+        *item = canonicalize_path(item)?;
+    }
+    for module in config.target_info.additional_modules.iter_mut() {
+        *module = canonicalize_path(module)?;
+    }
+
+    for fuzz_item in config.fuzzer_infos.iter_mut() {
+        fuzz_item.traces_directory_path = canonicalize_path(&fuzz_item.traces_directory_path)?;
+        fuzz_item.inputs_directory_path = canonicalize_path(&fuzz_item.inputs_directory_path)?;
+        if let QueueNameFormat::LibFuzzer {
+            log_path,
+            seed_corpus_path,
+        }
+        | QueueNameFormat::HonggfuzzOutput {
+            log_path,
+            seed_corpus_path,
+        } = &mut fuzz_item.queue_name_format
+        {
+            *log_path = canonicalize_path(log_path)?;
+            *seed_corpus_path = canonicalize_path(seed_corpus_path)?;
+        }
+    }
+
+    if let Some(tls) = config.server.tls.as_mut() {
+        tls.certificate_path = canonicalize_path(&tls.certificate_path)?;
+        tls.private_key_path = canonicalize_path(&tls.private_key_path)?;
+    }
+
+    Ok(config)
 }
 
 impl UserConfig {
-    pub fn parse(config_path: &str) -> UserConfig {
-        let p = fs::canonicalize(PathBuf::from(&config_path))
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-        let f = fs::read_to_string(&p).expect("Failed opening configuration file");
-        canonicalize(from_str(&f).unwrap())
+    pub fn parse(config_path: &str) -> Result<UserConfig, ConfigError> {
+        let p = canonicalize_path(config_path)?;
+        let f = fs::read_to_string(&p).map_err(|source| ConfigError::Io {
+            path: p.to_string(),
+            source,
+        })?;
+        canonicalize(from_str(&f).map_err(ConfigError::Parse)?)
     }
 }
 
@@ -133,7 +339,7 @@ mod tests {
     #[test]
     fn test_user_config_simple() {
         let config = "test_data/test_config_1.ron";
-        let parsed_config = UserConfig::parse(config);
+        let parsed_config = UserConfig::parse(config).unwrap();
         // println!("{:?}", parsed_config);
         assert_eq!(parsed_config.fuzzer_infos.len(), 2);
         assert!(parsed_config
@@ -150,15 +356,97 @@ mod tests {
     #[should_panic(expected = "MissingStructField")]
     fn test_user_config_incomplete() {
         let config = "test_data/test_config_2.ron";
-        let _ = UserConfig::parse(config);
+        let _ = UserConfig::parse(config).unwrap();
+    }
+
+    #[test]
+    fn test_user_config_missing_file() {
+        let config = "test_data/does_not_exist.ron";
+        assert!(matches!(
+            UserConfig::parse(config),
+            Err(ConfigError::Io { .. })
+        ));
     }
 
     #[test]
     fn test_user_config_optionals() {
         let config = "test_data/test_config_3.ron";
-        let parsed_config = UserConfig::parse(config);
+        let parsed_config = UserConfig::parse(config).unwrap();
         // println!("{:?}", parsed_config);
         assert_eq!(parsed_config.fuzzer_infos.len(), 2);
         assert_eq!(parsed_config.target_info.target_include_filter.len(), 0);
+        assert_eq!(
+            parsed_config.fuzzer_infos[0].queue_name_format,
+            QueueNameFormat::PatchedAflPlusPlus
+        );
+        assert_eq!(
+            parsed_config.fuzzer_infos[1].queue_name_format,
+            QueueNameFormat::Regex("id:(?P<id>\\d+)".to_string())
+        );
+        assert_eq!(parsed_config.tracer, TracerConfig::default());
+        assert_eq!(parsed_config.server, ServerConfig::default());
+        assert_eq!(parsed_config.server.auth, None);
+        assert_eq!(
+            parsed_config.target_info.coverage_backend,
+            CoverageBackend::DrCov
+        );
+        assert!(parsed_config.target_info.additional_modules.is_empty());
+    }
+
+    #[test]
+    fn test_user_config_tracer() {
+        let config = "test_data/test_config_4.ron";
+        let parsed_config = UserConfig::parse(config).unwrap();
+        assert_eq!(
+            parsed_config.tracer.qemu_trace_path,
+            "/opt/AFLplusplus/afl-qemu-trace"
+        );
+        assert_eq!(parsed_config.tracer.target_arguments, vec!["-a", "@@"]);
+        assert_eq!(parsed_config.tracer.timeout_ms, 2000);
+        assert_eq!(
+            parsed_config.tracer.full_trace_plugin_argument.as_deref(),
+            Some("fulltrace=on")
+        );
+        // not given in the file
+        assert_eq!(parsed_config.tracer.jobs, 0);
+        assert_eq!(
+            parsed_config.target_info.coverage_backend,
+            CoverageBackend::LlvmCovJson
+        );
+        assert_eq!(parsed_config.server.bind_address, "0.0.0.0");
+        assert_eq!(parsed_config.server.port, 8081);
+        assert_eq!(parsed_config.server.log_level, LogLevel::Warn);
+        assert_eq!(parsed_config.server.watch_interval_ms, Some(5000));
+        // not given in the file
+        assert_eq!(parsed_config.server.workers, 1);
+        assert_eq!(parsed_config.server.snapshot_path, None);
+        assert_eq!(
+            parsed_config.server.allowed_origins,
+            vec!["https://seedui.example.com"]
+        );
+        assert_eq!(
+            parsed_config.server.auth,
+            Some(AuthConfig::BearerToken("secret".to_string()))
+        );
+        let tls = parsed_config.server.tls.unwrap();
+        assert!(tls.certificate_path.starts_with('/'));
+        assert!(tls
+            .certificate_path
+            .ends_with("test_data/test_config_1.ron"));
+        assert!(tls
+            .private_key_path
+            .ends_with("test_data/test_config_3.ron"));
+        let module_paths: Vec<&str> = parsed_config.target_info.module_paths().collect();
+        assert_eq!(module_paths.len(), 2);
+        assert!(module_paths[0].ends_with("test_data/test_config_4.ron"));
+        assert!(module_paths[1].starts_with('/'));
+        assert!(module_paths[1].ends_with("test_data/test_config_1.ron"));
+    }
+
+    #[test]
+    fn test_log_level_from_str() {
+        assert_eq!("debug".parse(), Ok(LogLevel::Debug));
+        assert_eq!("WARN".parse(), Ok(LogLevel::Warn));
+        assert!("verbose".parse::<LogLevel>().is_err());
     }
 }
@@ -3,9 +3,9 @@ use std::{
     fmt, usize,
 };
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize)]
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct InputId(usize);
 
 impl InputId {
@@ -18,7 +18,7 @@ impl InputId {
     }
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct InputMeta {
     pub id: InputId,
     pub fuzz_input_id: u32,
@@ -27,6 +27,8 @@ pub struct InputMeta {
     pub fuzzer_coverage: u32,
     pub executed_on: i64,
     pub source_line_coverage: HashSet<LineId>,
+    pub function_coverage: HashSet<FunctionId>,
+    pub edge_coverage: HashSet<EdgeId>,
     pub parents: Vec<u32>,
     pub is_initial_seed: bool,
     pub fuzzer_configuration: u32,
@@ -49,6 +51,8 @@ impl InputMeta {
             fuzzer_coverage: 0,
             executed_on: 0,
             source_line_coverage: HashSet::new(),
+            function_coverage: HashSet::new(),
+            edge_coverage: HashSet::new(),
             parents: Vec::new(),
             is_initial_seed: false,
             fuzzer_configuration: 0,
@@ -82,7 +86,7 @@ impl fmt::Debug for InputMeta {
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize)]
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct FileId(usize);
 
 impl FileId {
@@ -95,7 +99,7 @@ impl FileId {
     }
 }
 
-#[derive(Eq, PartialEq, Clone)]
+#[derive(Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct FileMeta {
     pub name: String,
     pub lines: HashSet<LineId>,
@@ -125,7 +129,7 @@ impl fmt::Debug for FileMeta {
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize)]
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct LineId(FileId, u32);
 
 impl LineId {
@@ -142,7 +146,30 @@ impl LineId {
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Clone, Serialize)]
+/// A transfer of control from the end of one basic block to the start of the next, between two
+/// lines of the same source file
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct EdgeId(LineId, LineId);
+
+impl EdgeId {
+    pub fn new(from: LineId, to: LineId) -> Self {
+        EdgeId(from, to)
+    }
+
+    pub fn from(&self) -> LineId {
+        self.0
+    }
+
+    pub fn to(&self) -> LineId {
+        self.1
+    }
+
+    pub fn file(&self) -> FileId {
+        self.0.file()
+    }
+}
+
+#[derive(Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
 pub struct LineMeta {
     pub file_id: FileId,
     pub line_num: u32,
@@ -165,3 +192,28 @@ impl fmt::Debug for LineMeta {
             .finish()
     }
 }
+
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct FunctionId(usize);
+
+impl FunctionId {
+    pub fn new(id: usize) -> Self {
+        FunctionId(id)
+    }
+
+    pub fn as_usize(&self) -> usize {
+        self.0
+    }
+}
+
+/// A function of the target as found in its DWARF information
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct FunctionMeta {
+    pub id: FunctionId,
+    pub name: String,
+    pub file_id: FileId,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub hit_count: u32,
+    pub fuzzer_configuration_ids: Vec<u32>,
+}
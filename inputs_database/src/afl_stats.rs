@@ -0,0 +1,148 @@
+use std::{fs, path::Path};
+
+use crate::IngestionError;
+
+/// What a stock AFL++ instance records about its campaign next to the `queue` directory.
+///
+/// Stock queue entry names only hold the milliseconds since the start of the campaign and no
+/// coverage, `fuzzer_stats` provides the start time and `plot_data` the number of edges found
+/// over time.
+#[derive(Clone, Debug, Default)]
+pub struct AflRunStats {
+    // milliseconds since the epoch
+    start_time: i64,
+    // (milliseconds since the start, edges found), ordered by time
+    coverage_samples: Vec<(i64, u32)>,
+}
+
+fn run_stats_error(path: &Path, reason: String) -> IngestionError {
+    IngestionError::RunStats {
+        path: path.to_string_lossy().into_owned(),
+        reason,
+    }
+}
+
+fn read_to_string(path: &Path) -> Result<String, IngestionError> {
+    fs::read_to_string(path).map_err(|e| run_stats_error(path, e.to_string()))
+}
+
+// "start_time        : 1753701940" in seconds
+fn parse_start_time(path: &Path, fuzzer_stats: &str) -> Result<i64, IngestionError> {
+    fuzzer_stats
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim() == "start_time")
+        .and_then(|(_, value)| value.trim().parse::<i64>().ok())
+        .map(|seconds| seconds * 1000)
+        .ok_or_else(|| run_stats_error(path, "no start_time".to_string()))
+}
+
+// "# relative_time, cycles_done, cur_item, corpus_count, ..., edges_found" followed by one row
+// every few seconds. Older AFL++ versions write unix_time instead of relative_time.
+fn parse_coverage_samples(
+    path: &Path,
+    plot_data: &str,
+    start_time: i64,
+) -> Result<Vec<(i64, u32)>, IngestionError> {
+    let mut lines = plot_data.lines();
+    let header: Vec<&str> = lines
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches('#')
+        .split(',')
+        .map(|column| column.trim())
+        .collect();
+    let column = |name: &str| header.iter().position(|column| *column == name);
+    let Some(edges_found) = column("edges_found") else {
+        return Err(run_stats_error(path, "no edges_found column".to_string()));
+    };
+    let (time, time_offset) = match (column("relative_time"), column("unix_time")) {
+        (Some(relative_time), _) => (relative_time, 0),
+        (None, Some(unix_time)) => (unix_time, start_time),
+        (None, None) => return Err(run_stats_error(path, "no time column".to_string())),
+    };
+
+    let mut coverage_samples = Vec::new();
+    for line in lines {
+        let row: Vec<&str> = line.split(',').map(|value| value.trim()).collect();
+        let (Some(Ok(seconds)), Some(Ok(edges))) = (
+            row.get(time).map(|value| value.parse::<i64>()),
+            row.get(edges_found).map(|value| value.parse::<u32>()),
+        ) else {
+            // the last row may be incomplete if AFL++ was still running
+            continue;
+        };
+        coverage_samples.push((seconds * 1000 - time_offset, edges));
+    }
+    coverage_samples.sort_by_key(|(time, _)| *time);
+
+    Ok(coverage_samples)
+}
+
+impl AflRunStats {
+    /// Reads `fuzzer_stats` and `plot_data` of the AFL++ instance in `output_directory`, i.e. the
+    /// parent of its `queue` directory.
+    pub fn read(output_directory: &Path) -> Result<AflRunStats, IngestionError> {
+        let fuzzer_stats_path = output_directory.join("fuzzer_stats");
+        let start_time =
+            parse_start_time(&fuzzer_stats_path, &read_to_string(&fuzzer_stats_path)?)?;
+
+        let plot_data_path = output_directory.join("plot_data");
+        let coverage_samples = parse_coverage_samples(
+            &plot_data_path,
+            &read_to_string(&plot_data_path)?,
+            start_time,
+        )?;
+
+        Ok(AflRunStats {
+            start_time,
+            coverage_samples,
+        })
+    }
+
+    /// Wall clock time in milliseconds of an input found `time` milliseconds into the campaign
+    pub fn executed_on(&self, time: i64) -> i64 {
+        self.start_time + time
+    }
+
+    /// Edges found by the fuzzer once the input found `time` milliseconds into the campaign was
+    /// added, from the first `plot_data` row written at or after `time`.
+    pub fn edges_found(&self, time: i64) -> u32 {
+        let index = self
+            .coverage_samples
+            .partition_point(|(sample_time, _)| *sample_time < time);
+        self.coverage_samples
+            .get(index)
+            .or(self.coverage_samples.last())
+            .map_or(0, |(_, edges)| *edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_afl_run_stats() {
+        let run_stats = AflRunStats::read(Path::new("../test_data/afl_stock")).unwrap();
+        assert_eq!(run_stats.executed_on(0), 1753701940000);
+        assert_eq!(run_stats.executed_on(7520), 1753701947520);
+        assert_eq!(run_stats.edges_found(0), 98);
+        assert_eq!(run_stats.edges_found(4000), 112);
+        assert_eq!(run_stats.edges_found(5000), 112);
+        assert_eq!(run_stats.edges_found(7520), 123);
+        assert_eq!(run_stats.edges_found(60000), 125);
+    }
+
+    #[test]
+    fn test_afl_run_stats_unix_time() {
+        let coverage_samples = parse_coverage_samples(
+            Path::new("plot_data"),
+            "# unix_time, cycles_done, cur_path, paths_total, pending_total, pending_favs, map_size, unique_crashes, unique_hangs, max_depth, execs_per_sec, edges_found\n1753701945, 0, 0, 4, 4, 1, 0.02%, 0, 0, 2, 1020.00, 112\n",
+            1753701940000,
+        )
+        .unwrap();
+        assert_eq!(coverage_samples, vec![(5000, 112)]);
+        assert!(AflRunStats::read(Path::new("../test_data/does_not_exist")).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{fuzzer_log_error, AmbiguousParent, IngestionError};
+
+/// Metadata of one file of a Honggfuzz output directory or of its initial seeds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HonggfuzzEntry {
+    pub id: u32,
+    pub is_initial_seed: bool,
+    pub executed_on: i64,
+    pub edges_found: u32,
+    pub parents: Vec<u32>,
+}
+
+/// Inputs of a Honggfuzz run built with `external/rename_seeds_honggfuzz.patch`, read directly from
+/// its output directory and log.
+///
+/// The patched Honggfuzz names its inputs
+/// `id:…,executed_on:…,edges_found:…,<crc64f><crc64r>.<size>.honggfuzz.cov` and logs
+/// `Adding file '…' to the corpus directory '…' from source '…'` for each of them. A source is
+/// matched by its file name, or else by its content, i.e. the `<crc64f><crc64r>.<size>` part.
+/// Sources with no or more than one matching input are reported instead of guessed.
+#[derive(Clone, Debug, Default)]
+pub struct HonggfuzzCorpus {
+    entries: HashMap<String, HonggfuzzEntry>,
+    ambiguous_parents: Vec<AmbiguousParent>,
+}
+
+fn file_name_of(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map_or_else(|| path.to_string(), |f| f.to_string_lossy().into_owned())
+}
+
+fn list_file_names(directory: &Path) -> Result<Vec<String>, IngestionError> {
+    let entries = fs::read_dir(directory).map_err(|e| fuzzer_log_error(directory, e))?;
+    let mut file_names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    file_names.sort();
+    Ok(file_names)
+}
+
+// (added file name, source file name) of every "Adding file" line, in the order of the log
+fn parse_log(log: &str) -> Vec<(String, String)> {
+    let mut added_files = Vec::new();
+    for line in log.lines() {
+        let Some((_, rest)) = line.split_once("Adding file '") else {
+            continue;
+        };
+        let Some((file_path, rest)) = rest.split_once('\'') else {
+            continue;
+        };
+        let Some((_, source_path)) = rest.split_once("from source '") else {
+            continue;
+        };
+        let source_path = source_path.strip_suffix('\'').unwrap_or(source_path);
+        added_files.push((file_name_of(file_path), file_name_of(source_path)));
+    }
+    added_files
+}
+
+// "<crc64f><crc64r>.<size>.honggfuzz.cov", identical for inputs with the same content
+fn content_key(file_name: &str) -> &str {
+    file_name.rsplit(',').next().unwrap_or(file_name)
+}
+
+// (id, executed_on, edges_found) from the name of a patched Honggfuzz input
+fn parse_name(file_name: &str) -> Option<(u32, i64, u32)> {
+    let mut id = None;
+    let mut executed_on = None;
+    let mut edges_found = 0;
+    for (name, value) in file_name.split(',').filter_map(|f| f.split_once(':')) {
+        match name {
+            "id" => id = value.parse().ok(),
+            "executed_on" => executed_on = value.parse().ok(),
+            "edges_found" => edges_found = value.parse().unwrap_or_default(),
+            _ => {}
+        }
+    }
+    Some((id?, executed_on?, edges_found))
+}
+
+impl HonggfuzzCorpus {
+    pub fn read(
+        output_directory: &Path,
+        log_path: &Path,
+        seed_corpus_directory: &Path,
+    ) -> Result<HonggfuzzCorpus, IngestionError> {
+        let log = fs::read_to_string(log_path).map_err(|e| fuzzer_log_error(log_path, e))?;
+        let added_files = parse_log(&log);
+
+        // inputs removed from the output directory may still be the source of others
+        let mut file_names: Vec<String> = added_files.iter().map(|(f, _)| f.clone()).collect();
+        file_names.extend(list_file_names(output_directory)?);
+
+        let mut entries: HashMap<String, HonggfuzzEntry> = HashMap::new();
+        let mut content_keys: HashMap<&str, Vec<u32>> = HashMap::new();
+        for file_name in file_names.iter() {
+            if entries.contains_key(file_name) {
+                continue;
+            }
+            let Some((id, executed_on, edges_found)) = parse_name(file_name) else {
+                continue;
+            };
+            let ids = content_keys.entry(content_key(file_name)).or_default();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+            entries.insert(
+                file_name.clone(),
+                HonggfuzzEntry {
+                    id,
+                    is_initial_seed: false,
+                    executed_on,
+                    edges_found,
+                    parents: Vec::new(),
+                },
+            );
+        }
+
+        let first_seed_id = entries.values().map(|e| e.id + 1).max().unwrap_or(1);
+        for (id, file_name) in (first_seed_id..).zip(list_file_names(seed_corpus_directory)?) {
+            entries.insert(
+                file_name,
+                HonggfuzzEntry {
+                    id,
+                    is_initial_seed: true,
+                    executed_on: 0,
+                    edges_found: 0,
+                    parents: Vec::new(),
+                },
+            );
+        }
+
+        let mut ambiguous_parents = Vec::new();
+        let mut parents: Vec<(String, u32)> = Vec::new();
+        for (file_name, source) in added_files.iter() {
+            let Some(entry) = entries.get(file_name) else {
+                continue;
+            };
+            let candidates = match entries.get(source) {
+                Some(source_entry) => vec![source_entry.id],
+                None => content_keys
+                    .get(content_key(source))
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            if candidates.len() == 1 {
+                parents.push((file_name.clone(), candidates[0]));
+            } else {
+                ambiguous_parents.push(AmbiguousParent {
+                    fuzz_input_id: entry.id,
+                    file_name: file_name.clone(),
+                    source: source.clone(),
+                    candidates,
+                });
+            }
+        }
+
+        let seed_names: HashMap<u32, String> = entries
+            .iter()
+            .filter(|(_, e)| e.is_initial_seed)
+            .map(|(name, e)| (e.id, name.clone()))
+            .collect();
+        for (file_name, parent_id) in parents {
+            let (executed_on, edges_found) = {
+                let entry = entries.get_mut(&file_name).unwrap();
+                entry.parents.push(parent_id);
+                (entry.executed_on, entry.edges_found)
+            };
+            // initial seeds are as old as their first child, like in the renaming script
+            if let Some(seed) = seed_names.get(&parent_id).and_then(|n| entries.get_mut(n)) {
+                if seed.executed_on == 0 || executed_on < seed.executed_on {
+                    seed.executed_on = executed_on;
+                    seed.edges_found = edges_found;
+                }
+            }
+        }
+
+        Ok(HonggfuzzCorpus {
+            entries,
+            ambiguous_parents,
+        })
+    }
+
+    /// Returns the entry of the output directory or initial seed file named `file_name`
+    pub fn get(&self, file_name: &str) -> Option<&HonggfuzzEntry> {
+        self.entries.get(file_name)
+    }
+
+    pub fn get_ambiguous_parents(&self) -> &Vec<AmbiguousParent> {
+        &self.ambiguous_parents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let added_files = parse_log(
+            "[2025-08-11T16:20:33+0200][I][4711] input_writeCovFile():352 Adding file '/out/id:1,executed_on:1753701941000,edges_found:10,0000000000000001aaaaaaaaaaaaaaaa.00000002.honggfuzz.cov' to the corpus directory '/out' from source 'a.txt'\n\
+             Size:2 (i,b,hw,ed,ip,cmp): 0/0/0/10/0/3, Tot:0/0/0/10/0/3\n",
+        );
+        assert_eq!(
+            added_files,
+            vec![(
+                "id:1,executed_on:1753701941000,edges_found:10,0000000000000001aaaaaaaaaaaaaaaa.00000002.honggfuzz.cov".to_string(),
+                "a.txt".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_honggfuzz_corpus() {
+        let test_data_dir = Path::new("../test_data/honggfuzz");
+        let corpus = HonggfuzzCorpus::read(
+            &test_data_dir.join("hfuzz-out"),
+            &test_data_dir.join("hfuzz.log"),
+            &test_data_dir.join("seeds"),
+        )
+        .unwrap();
+
+        let parents_of = |id: u32| {
+            corpus
+                .entries
+                .values()
+                .find(|e| e.id == id && !e.is_initial_seed)
+                .unwrap()
+                .parents
+                .clone()
+        };
+        assert_eq!(parents_of(1), vec![9]);
+        assert_eq!(parents_of(2), vec![1]);
+        // matched by content, the source was logged with an outdated name
+        assert_eq!(parents_of(3), vec![1]);
+        assert_eq!(parents_of(4), vec![10]);
+        assert_eq!(parents_of(5), vec![2]);
+        assert_eq!(parents_of(6), vec![4]);
+        assert!(parents_of(7).is_empty());
+        assert!(parents_of(8).is_empty());
+
+        let seed = corpus.get("a.txt").unwrap();
+        assert!(seed.is_initial_seed);
+        assert_eq!(seed.id, 9);
+        assert_eq!(seed.executed_on, 1753701941000);
+        assert_eq!(seed.edges_found, 10);
+
+        let ambiguous_parents = corpus.get_ambiguous_parents();
+        assert_eq!(ambiguous_parents.len(), 2);
+        assert_eq!(ambiguous_parents[0].fuzz_input_id, 7);
+        assert_eq!(ambiguous_parents[0].candidates, vec![5, 6]);
+        assert_eq!(ambiguous_parents[1].fuzz_input_id, 8);
+        assert!(ambiguous_parents[1].candidates.is_empty());
+
+        let missing_seeds = HonggfuzzCorpus::read(
+            &test_data_dir.join("hfuzz-out"),
+            &test_data_dir.join("hfuzz.log"),
+            &test_data_dir.join("missing"),
+        );
+        assert!(matches!(
+            missing_seeds,
+            Err(IngestionError::FuzzerLog { .. })
+        ));
+    }
+}
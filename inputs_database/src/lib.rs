@@ -1,16 +1,206 @@
 use std::{
     collections::{HashMap, HashSet},
-    fs::{self, File},
-    io::Read,
+    error::Error,
+    fmt, fs, io,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
 };
 
-use config::{FuzzerConfig, TargetConfig};
+use config::{FuzzerConfig, QueueNameFormat, TargetConfig};
+use serde::{Deserialize, Serialize};
 use sut_database::SUT;
-use trace_map::{Trace, TraceMap};
+use trace_map::{ResolvedTrace, Trace, TraceError, TraceMap};
 use custom_types::*;
 
-#[derive(Clone)]
+mod afl_stats;
+pub use afl_stats::AflRunStats;
+
+mod honggfuzz;
+pub use honggfuzz::{HonggfuzzCorpus, HonggfuzzEntry};
+
+mod libfuzzer;
+pub use libfuzzer::{LibFuzzerCorpus, LibFuzzerEntry};
+
+mod queue_name;
+pub use queue_name::{
+    queue_name_parser_for, AflNameParser, HonggfuzzNameParser, HonggfuzzOutputParser,
+    LibFuzzerNameParser, PatchedAflNameParser, QueueNameParser, RegexNameParser,
+};
+
+#[derive(Debug)]
+pub enum IngestionError {
+    InvalidFileName { file_name: String, reason: String },
+    InvalidNameFormat { pattern: String, reason: String },
+    RunStats { path: String, reason: String },
+    FuzzerLog { path: String, reason: String },
+    Trace(TraceError),
+}
+
+impl fmt::Display for IngestionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IngestionError::InvalidFileName { file_name, reason } => {
+                write!(f, "invalid input file name {}: {}", file_name, reason)
+            }
+            IngestionError::InvalidNameFormat { pattern, reason } => {
+                write!(f, "invalid queue name format {:?}: {}", pattern, reason)
+            }
+            IngestionError::RunStats { path, reason } => {
+                write!(f, "unable to read fuzzer statistics {}: {}", path, reason)
+            }
+            IngestionError::FuzzerLog { path, reason } => {
+                write!(
+                    f,
+                    "unable to read fuzzer log or corpus {}: {}",
+                    path, reason
+                )
+            }
+            IngestionError::Trace(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for IngestionError {}
+
+impl From<TraceError> for IngestionError {
+    fn from(value: TraceError) -> Self {
+        IngestionError::Trace(value)
+    }
+}
+
+/// Why the inputs of an initial seed cannot be compared
+#[derive(Debug)]
+pub enum CompareError {
+    UnknownFuzzerConfiguration(u32),
+    UnknownInitialSeed(u32),
+    UnreadableInput { path: String, reason: String },
+}
+
+impl fmt::Display for CompareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompareError::UnknownFuzzerConfiguration(id) => {
+                write!(f, "unknown fuzzer configuration {}", id)
+            }
+            CompareError::UnknownInitialSeed(id) => write!(f, "unknown initial seed {}", id),
+            CompareError::UnreadableInput { path, reason } => {
+                write!(f, "unable to read input {}: {}", path, reason)
+            }
+        }
+    }
+}
+
+impl Error for CompareError {}
+
+/// An input that was left out of the databases because its trace could not be ingested
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SkippedInput {
+    pub fuzzer_configuration_id: u32,
+    pub file_name: String,
+    pub reason: String,
+}
+
+/// An input whose trace file name references parents that are not in the databases
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrphanedInput {
+    pub fuzz_input_id: u32,
+    pub file_name_stem: String,
+    pub missing_parents: Vec<u32>,
+}
+
+/// An input whose source, as recorded by the fuzzer, matches none or several inputs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AmbiguousParent {
+    pub fuzz_input_id: u32,
+    pub file_name: String,
+    pub source: String,
+    // ids of the inputs the source matches, empty if it matches none
+    pub candidates: Vec<u32>,
+}
+
+/// Which inputs of a fuzzer configuration reached a function
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionCoverage {
+    // fuzz input ids of the initial seeds that cover the function themselves
+    pub initial_seeds: Vec<u32>,
+    // fuzz input ids of the other inputs that cover the function, in the order they were found
+    pub inputs: Vec<u32>,
+    pub first_executed_on: Option<i64>,
+    // fuzz input ids of the initial seeds the first of `inputs` descends from
+    pub first_input_initial_seeds: Vec<u32>,
+}
+
+/// How often a line was executed by a selection of inputs
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineHeat {
+    pub line: u32,
+    // sum of the execution counts of all inputs
+    pub executions: u64,
+    // number of inputs executing the line
+    pub inputs: u32,
+    // highest execution count of a single input, tells a hot loop from a line every input
+    // executes once
+    pub max_executions: u64,
+}
+
+/// An edge within a source file taken by an initial seed or the inputs descending from it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeCoverage {
+    pub from_line: u32,
+    pub to_line: u32,
+    // whether the initial seed takes the edge itself
+    pub initial_seed: bool,
+    // number of inputs descending from the initial seed that take the edge
+    pub descendants: u32,
+}
+
+/// What happened while ingesting the traces of one fuzzer configuration
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct IngestionReport {
+    pub traces_seen: u32,
+    // file name stems of inputs whose drcov file is empty, they are ingested without coverage
+    pub empty_traces: Vec<String>,
+    pub orphaned_inputs: Vec<OrphanedInput>,
+    pub ambiguous_parents: Vec<AmbiguousParent>,
+    pub blocks_filtered_by_path: u64,
+    pub blocks_without_line: u64,
+}
+
+// the log, corpus or seed corpus of libFuzzer or Honggfuzz
+fn fuzzer_log_error(path: &Path, e: io::Error) -> IngestionError {
+    IngestionError::FuzzerLog {
+        path: path.to_string_lossy().into_owned(),
+        reason: e.to_string(),
+    }
+}
+
+fn canonical_file_name(file_name: &str) -> Result<String, IngestionError> {
+    match fs::canonicalize(PathBuf::from(file_name)) {
+        Ok(p) => Ok(p.to_string_lossy().into_owned()),
+        Err(source) => Err(IngestionError::Trace(TraceError::Io {
+            path: file_name.to_string(),
+            source,
+        })),
+    }
+}
+
+type ResolvedInput = Result<(InputMeta, ResolvedTrace), IngestionError>;
+
+/// Traces of a fuzzer configuration resolved by `InputsDatabase::resolve_inputs`, sorted in the
+/// order they are merged in
+pub struct ResolvedInputs {
+    fuzzer_configuration_id: u32,
+    file_names: Vec<String>,
+    trace_map: Arc<TraceMap>,
+    queue_name_parser: Box<dyn QueueNameParser>,
+    resolved_inputs: Vec<(usize, ResolvedInput)>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct InputsDatabase {
     fuzzer_configurations: HashMap<u32, FuzzerConfig>,
     initial_seeds_input_meta_map: HashMap<u32, HashMap<InputId, InputMeta>>,
@@ -19,8 +209,19 @@ pub struct InputsDatabase {
     input_id_to_trace_map: HashMap<InputId, Trace>,
     input_id_to_input_meta_map: HashMap<InputId, InputMeta>,
     fuzzer_id_input_id_map: HashMap<u32, Vec<InputId>>,
+    // sorted by InputId, the order the inputs were merged in
     fuzzer_id_initial_seeds_id_to_children_input_id_map: HashMap<(u32, u32), Vec<InputId>>,
+    // the inputs up to this InputId were linked to their initial seeds by post_process
+    num_linked_inputs: usize,
     min_max_times: HashMap<u32, (i64, i64)>,
+    skipped_inputs: Vec<SkippedInput>,
+    ingestion_reports: HashMap<u32, IngestionReport>,
+    // every ingested trace file, so that watching a traces directory only picks up new ones and
+    // the skipped ones again
+    trace_files: HashMap<u32, HashSet<String>>,
+    // one shared resolver per target binary, rebuilt lazily after loading a snapshot
+    #[serde(skip)]
+    trace_maps: HashMap<String, Arc<TraceMap>>,
 }
 
 impl Default for InputsDatabase {
@@ -40,27 +241,62 @@ impl InputsDatabase {
             fuzzer_id_input_id_map: HashMap::new(),
             fuzzer_input_id_to_input_id_map: HashMap::new(),
             fuzzer_id_initial_seeds_id_to_children_input_id_map: HashMap::new(),
+            num_linked_inputs: 0,
             min_max_times: HashMap::new(),
+            skipped_inputs: Vec::new(),
+            ingestion_reports: HashMap::new(),
+            trace_files: HashMap::new(),
+            trace_maps: HashMap::new(),
         }
     }
 
     pub fn add_fuzzer_configuration(&mut self, fuzzer_config: &FuzzerConfig) {
+        let fuzzer_configuration_id = fuzzer_config.fuzzer_configuration_id;
         self.fuzzer_configurations
-            .insert(fuzzer_config.fuzzer_configuration_id, fuzzer_config.clone());
+            .insert(fuzzer_configuration_id, fuzzer_config.clone());
+        // a fuzzer configuration may have no initial seeds, e.g. libFuzzer started with an empty
+        // seed corpus, or no inputs yet
+        self.initial_seeds_input_meta_map
+            .entry(fuzzer_configuration_id)
+            .or_default();
+        self.fuzzer_id_initial_seeds_map
+            .entry(fuzzer_configuration_id)
+            .or_default();
+        self.fuzzer_id_input_id_map
+            .entry(fuzzer_configuration_id)
+            .or_default();
     }
 
+    /// Adds the single input of `file_name`, like `add_inputs` it is recorded in
+    /// `get_skipped_inputs` if it cannot be ingested.
     pub fn add_input(
         &mut self,
         file_name: &str,
         target_config: &TargetConfig,
-        sut_db: SUT,
+        sut_db: &mut SUT,
         fuzzer_configuration_id: u32,
-    ) -> SUT {
-        if file_name.contains("orig") {
-            self.add_initial_seed(file_name, target_config, sut_db, fuzzer_configuration_id)
-        } else {
-            self.add_trace_input(file_name, target_config, sut_db, fuzzer_configuration_id)
-        }
+    ) -> Result<(), IngestionError> {
+        let file_names = [file_name.to_string()];
+        let ResolvedInputs {
+            trace_map,
+            mut resolved_inputs,
+            ..
+        } = self.resolve_inputs(&file_names, target_config, fuzzer_configuration_id, 1)?;
+        self.trace_maps
+            .entry(target_config.target_path.to_string())
+            .or_insert_with(|| trace_map.clone());
+        self.count_traces_seen(&file_names, fuzzer_configuration_id);
+
+        // one for each file name
+        let (_, resolved_input) = resolved_inputs.swap_remove(0);
+        self.merge_resolved_input(
+            file_name,
+            fuzzer_configuration_id,
+            resolved_input,
+            &trace_map,
+            target_config,
+            sut_db,
+        )
     }
 
     fn record_line_coverage(
@@ -78,96 +314,255 @@ impl InputsDatabase {
         }
     }
 
-    // id:000005,time:0,executed_on:1754925633575,execs:0,edges_found:8388608,orig:253.txt
-    fn add_initial_seed(
+    /// Returns the parser for the queue entry names of a fuzzer configuration, inputs of unknown
+    /// configurations are expected to follow the patched AFL++ naming.
+    pub fn get_queue_name_parser(
+        &self,
+        fuzzer_configuration_id: u32,
+    ) -> Result<Box<dyn QueueNameParser>, IngestionError> {
+        match self.fuzzer_configurations.get(&fuzzer_configuration_id) {
+            Some(fuzzer_config) => queue_name_parser_for(fuzzer_config),
+            None => Ok(Box::new(PatchedAflNameParser)),
+        }
+    }
+
+    /// Adds all `file_names` of a fuzzer configuration using `num_workers` threads, see
+    /// `resolve_inputs` and `merge_inputs`.
+    ///
+    /// It may be called again with the traces written since, e.g. by a running campaign, followed
+    /// by another `post_process`.
+    pub fn add_inputs(
         &mut self,
-        file_name: &str,
+        file_names: &[String],
         target_config: &TargetConfig,
-        mut sut_db: SUT,
+        sut_db: &mut SUT,
         fuzzer_configuration_id: u32,
-    ) -> SUT {
-        let trace_map = TraceMap::new(&target_config.target_path);
-        let absolute_file_name = PathBuf::from(file_name);
-        let file_stem = &absolute_file_name.file_stem().unwrap().to_str().unwrap();
-        let file_stem_split: Vec<&str> = file_stem.split("::").collect();
+        num_workers: usize,
+    ) -> Result<(), IngestionError> {
+        let resolved_inputs = self.resolve_inputs(
+            file_names,
+            target_config,
+            fuzzer_configuration_id,
+            num_workers,
+        )?;
+        self.merge_inputs(resolved_inputs, target_config, sut_db);
+        Ok(())
+    }
 
-        let input_id = InputId::new(&self.input_id_to_trace_map.len() + 1);
-        let mut input_metadata = InputMeta::new();
-        input_metadata.id = input_id;
-        input_metadata.is_initial_seed = true;
-        input_metadata.fuzzer_configuration = fuzzer_configuration_id;
-        input_metadata.file_name_stem = file_stem.to_string().to_owned();
-        for item in &file_stem_split[0..] {
-            if item.contains("id") {
-                input_metadata.fuzz_input_id =
-                    item.split(':').nth(1).unwrap().parse::<u32>().unwrap();
-            } else if item.contains("executed_on") {
-                input_metadata.executed_on =
-                    item.split(':').nth(1).unwrap().parse::<i64>().unwrap();
-            }
-        }
+    /// Parses the traces of `file_names` and resolves them to source locations in parallel, using
+    /// `num_workers` threads. Nothing is changed, so the databases can still be read meanwhile.
+    ///
+    /// Only an unusable target binary is returned as an error, the errors of single inputs are
+    /// kept until they are merged.
+    pub fn resolve_inputs(
+        &self,
+        file_names: &[String],
+        target_config: &TargetConfig,
+        fuzzer_configuration_id: u32,
+        num_workers: usize,
+    ) -> Result<ResolvedInputs, IngestionError> {
+        let trace_map = match self.trace_maps.get(&target_config.target_path) {
+            Some(trace_map) => trace_map.clone(),
+            None => Arc::new(TraceMap::for_target(target_config)?),
+        };
+        let queue_name_parser = self.get_queue_name_parser(fuzzer_configuration_id)?;
+        let next_index = AtomicUsize::new(0);
+        let num_workers = num_workers.clamp(1, file_names.len().max(1));
+
+        let mut resolved_inputs: Vec<(usize, ResolvedInput)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..num_workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut resolved = Vec::new();
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::Relaxed);
+                            let Some(file_name) = file_names.get(index) else {
+                                break;
+                            };
+                            let resolved_input = queue_name_parser
+                                .parse(file_name, fuzzer_configuration_id)
+                                .and_then(|input_metadata| {
+                                    let trace_file = canonical_file_name(file_name)?;
+                                    let resolved_trace =
+                                        trace_map.resolve(&trace_file, target_config)?;
+                                    Ok((input_metadata, resolved_trace))
+                                });
+                            resolved.push((index, resolved_input));
+                        }
+                        resolved
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+
+        resolved_inputs.sort_by_key(|(index, resolved_input)| {
+            let is_initial_seed = resolved_input
+                .as_ref()
+                .is_ok_and(|(input_metadata, _)| input_metadata.is_initial_seed);
+            (!is_initial_seed, *index)
+        });
+        Ok(ResolvedInputs {
+            fuzzer_configuration_id,
+            file_names: file_names.to_vec(),
+            trace_map,
+            queue_name_parser,
+            resolved_inputs,
+        })
+    }
 
-        let current_trace = trace_map.parse_with_config(
-            fs::canonicalize(absolute_file_name)
-                .unwrap()
-                .to_str()
-                .unwrap(),
-            target_config,
-            &mut sut_db,
-        );
+    /// Merges inputs resolved by `resolve_inputs` into the databases in the order of their file
+    /// names, initial seeds first, so the assigned `InputId`s do not depend on the number of
+    /// workers or on scheduling. Inputs that cannot be ingested are skipped and recorded in
+    /// `get_skipped_inputs`.
+    pub fn merge_inputs(
+        &mut self,
+        resolved_inputs: ResolvedInputs,
+        target_config: &TargetConfig,
+        sut_db: &mut SUT,
+    ) {
+        let ResolvedInputs {
+            fuzzer_configuration_id,
+            file_names,
+            trace_map,
+            queue_name_parser,
+            resolved_inputs,
+        } = resolved_inputs;
+        self.trace_maps
+            .entry(target_config.target_path.to_string())
+            .or_insert_with(|| trace_map.clone());
+        self.count_traces_seen(&file_names, fuzzer_configuration_id);
+
+        for (index, resolved_input) in resolved_inputs {
+            // the errors are recorded in the skipped inputs
+            let _ = self.merge_resolved_input(
+                &file_names[index],
+                fuzzer_configuration_id,
+                resolved_input,
+                &trace_map,
+                target_config,
+                sut_db,
+            );
+        }
+        self.ingestion_reports
+            .entry(fuzzer_configuration_id)
+            .or_default()
+            .ambiguous_parents = queue_name_parser.get_ambiguous_parents();
+    }
 
-        // parse line coverage data
-        for source_trace in &current_trace.unique_lines_set {
-            if !sut_db.get_line_meta(*source_trace).unwrap().is_comment {
-                self.record_line_coverage(
-                    &input_id,
-                    *source_trace,
-                    input_metadata.executed_on,
-                    fuzzer_configuration_id,
-                    &mut sut_db,
-                );
-                input_metadata.source_line_coverage.insert(*source_trace);
+    fn merge_resolved_input(
+        &mut self,
+        file_name: &str,
+        fuzzer_configuration_id: u32,
+        resolved_input: ResolvedInput,
+        trace_map: &TraceMap,
+        target_config: &TargetConfig,
+        sut_db: &mut SUT,
+    ) -> Result<(), IngestionError> {
+        let merged = resolved_input.and_then(|(input_metadata, resolved_trace)| {
+            self.merge_input(
+                input_metadata,
+                resolved_trace,
+                trace_map,
+                target_config,
+                sut_db,
+            )
+        });
+        match &merged {
+            Ok(()) => {
+                // ingested on another attempt, e.g. once the tracer finished writing it
+                self.skipped_inputs.retain(|skipped_input| {
+                    skipped_input.fuzzer_configuration_id != fuzzer_configuration_id
+                        || skipped_input.file_name != file_name
+                });
+                self.trace_files
+                    .entry(fuzzer_configuration_id)
+                    .or_default()
+                    .insert(file_name.to_string());
             }
+            Err(e) => self.skip_input(file_name, fuzzer_configuration_id, e.to_string()),
         }
+        merged
+    }
 
-        self.input_id_to_trace_map
-            .insert(input_id, current_trace.clone());
+    /// Records all `file_names` of a fuzzer configuration as skipped, for traces that could not be
+    /// resolved at all
+    pub fn skip_inputs(
+        &mut self,
+        file_names: &[String],
+        fuzzer_configuration_id: u32,
+        e: &IngestionError,
+    ) {
+        self.count_traces_seen(file_names, fuzzer_configuration_id);
+        for file_name in file_names {
+            self.skip_input(file_name, fuzzer_configuration_id, e.to_string());
+        }
+    }
 
-        self.fuzzer_id_initial_seeds_map
-            .entry(fuzzer_configuration_id)
-            .or_default()
-            .push(input_id);
-        self.fuzzer_input_id_to_input_id_map.insert(
-            (fuzzer_configuration_id, input_metadata.fuzz_input_id),
-            input_id,
-        );
-        self.initial_seeds_input_meta_map
+    /// Whether `file_name` of the fuzzer configuration was ingested, the traces that were skipped
+    /// may be given to `add_inputs` again
+    pub fn has_trace_file(&self, fuzzer_configuration_id: u32, file_name: &str) -> bool {
+        self.trace_files
+            .get(&fuzzer_configuration_id)
+            .is_some_and(|trace_files| trace_files.contains(file_name))
+    }
+
+    fn is_skipped(&self, file_name: &str, fuzzer_configuration_id: u32) -> bool {
+        self.skipped_inputs.iter().any(|skipped_input| {
+            skipped_input.fuzzer_configuration_id == fuzzer_configuration_id
+                && skipped_input.file_name == file_name
+        })
+    }
+
+    // traces that were skipped before are not counted again when they are retried
+    fn count_traces_seen(&mut self, file_names: &[String], fuzzer_configuration_id: u32) {
+        let num_new = file_names
+            .iter()
+            .filter(|file_name| !self.is_skipped(file_name, fuzzer_configuration_id))
+            .count();
+        self.ingestion_reports
             .entry(fuzzer_configuration_id)
             .or_default()
-            .insert(input_id, input_metadata);
+            .traces_seen += num_new as u32;
+    }
 
-        sut_db
+    // reported together once ingestion is done, see `get_skipped_inputs`
+    fn skip_input(&mut self, file_name: &str, fuzzer_configuration_id: u32, reason: String) {
+        if let Some(skipped_input) = self.skipped_inputs.iter_mut().find(|skipped_input| {
+            skipped_input.fuzzer_configuration_id == fuzzer_configuration_id
+                && skipped_input.file_name == file_name
+        }) {
+            skipped_input.reason = reason;
+            return;
+        }
+        self.skipped_inputs.push(SkippedInput {
+            fuzzer_configuration_id,
+            file_name: file_name.to_string(),
+            reason,
+        });
     }
 
-    // Assumes that the filename contains all the information from the fuzzer
-    fn add_trace_input(
+    /// Returns the resolver for the target of `target_config`, loading its DWARF information on
+    /// first use if the coverage backend needs it.
+    pub fn get_trace_map(
         &mut self,
-        file_name: &str,
         target_config: &TargetConfig,
-        mut sut_db: SUT,
-        fuzzer_configuration_id: u32,
-    ) -> SUT {
-        let trace_map = TraceMap::new(&target_config.target_path);
-        let absolute_file_name = PathBuf::from(file_name);
-        let file_stem = absolute_file_name.file_stem().unwrap().to_str().unwrap();
-        let file_stem_split: Vec<&str> = file_stem.split("::").collect();
-        let executed_on = file_stem_split[2]
-            .split(':')
-            .nth(1)
-            .unwrap()
-            .parse::<i64>()
-            .unwrap();
+    ) -> Result<Arc<TraceMap>, TraceError> {
+        if let Some(trace_map) = self.trace_maps.get(&target_config.target_path) {
+            return Ok(trace_map.clone());
+        }
+
+        let trace_map = Arc::new(TraceMap::for_target(target_config)?);
+        self.trace_maps
+            .insert(target_config.target_path.to_string(), trace_map.clone());
+        Ok(trace_map)
+    }
 
+    fn update_min_max_times(&mut self, fuzzer_configuration_id: u32, executed_on: i64) {
         if let std::collections::hash_map::Entry::Vacant(e) =
             self.min_max_times.entry(fuzzer_configuration_id)
         {
@@ -183,141 +578,295 @@ impl InputsDatabase {
                 previous_times.1 = executed_on;
             }
         }
+    }
 
-        let input_id = InputId::new(&self.input_id_to_trace_map.len() + 1);
-
-        let mut input_metadata = InputMeta::new();
-        input_metadata.fuzz_input_id = file_stem_split[1]
-            .split(':')
-            .nth(1)
-            .unwrap()
-            .parse::<u32>()
-            .unwrap();
-        input_metadata.fuzzer_configuration = fuzzer_configuration_id;
-        input_metadata.id = input_id;
-        input_metadata.file_name_stem = file_stem.to_string().to_owned();
-        input_metadata.executed_on = executed_on;
-        for item in &file_stem_split[2..] {
-            if item.contains("time") {
-                input_metadata.execution_time =
-                    item.split(':').nth(1).unwrap().parse::<i64>().unwrap();
-            } else if item.contains("execs") {
-                input_metadata.total_mutations_required_to_generate =
-                    item.split(':').nth(1).unwrap().parse::<u32>().unwrap();
-            } else if item.contains("edges_found") {
-                input_metadata.fuzzer_coverage =
-                    item.split(':').nth(1).unwrap().parse::<u32>().unwrap();
-            } else if item.contains("src") {
-                input_metadata.parents = item
-                    .split(':')
-                    .nth(1)
-                    .unwrap()
-                    .split('+')
-                    .filter_map(|s| s.trim().parse::<u32>().ok())
-                    .collect();
-            }
+    fn merge_input(
+        &mut self,
+        mut input_metadata: InputMeta,
+        resolved_trace: ResolvedTrace,
+        trace_map: &TraceMap,
+        target_config: &TargetConfig,
+        sut_db: &mut SUT,
+    ) -> Result<(), IngestionError> {
+        // map the trace first, nothing is recorded for the input if this fails
+        let is_empty_file = resolved_trace.is_empty_file();
+        let (current_trace, mapping_stats) =
+            trace_map.map_trace(resolved_trace, target_config, sut_db)?;
+
+        let fuzzer_configuration_id = input_metadata.fuzzer_configuration;
+        let report = self
+            .ingestion_reports
+            .entry(fuzzer_configuration_id)
+            .or_default();
+        report.blocks_filtered_by_path += mapping_stats.blocks_filtered_by_path;
+        report.blocks_without_line += mapping_stats.blocks_without_line;
+        if is_empty_file {
+            report
+                .empty_traces
+                .push(input_metadata.file_name_stem.clone());
+        }
+        if !input_metadata.is_initial_seed {
+            self.update_min_max_times(fuzzer_configuration_id, input_metadata.executed_on);
         }
 
-        let current_trace = trace_map.parse_with_config(
-            fs::canonicalize(absolute_file_name)
-                .unwrap()
-                .to_str()
-                .unwrap(),
-            target_config,
-            &mut sut_db,
-        );
+        let input_id = InputId::new(&self.input_id_to_trace_map.len() + 1);
+        input_metadata.id = input_id;
 
         // parse line coverage data
         for source_trace in &current_trace.unique_lines_set {
-            if !sut_db.get_line_meta(*source_trace).unwrap().is_comment {
+            // DWARF line numbers beyond the end of the source file (e.g. outdated sources) have no
+            // LineMeta
+            let is_code = sut_db
+                .get_line_meta(*source_trace)
+                .is_some_and(|line_meta| !line_meta.is_comment);
+            if is_code {
                 self.record_line_coverage(
                     &input_id,
                     *source_trace,
-                    executed_on,
+                    input_metadata.executed_on,
                     fuzzer_configuration_id,
-                    &mut sut_db,
+                    sut_db,
                 );
                 input_metadata.source_line_coverage.insert(*source_trace);
+                if let Some(function_id) = sut_db.get_function_for_line(*source_trace) {
+                    input_metadata.function_coverage.insert(function_id);
+                }
             }
         }
+        // edges between lines without code, e.g. of outdated sources, cannot be shown
+        input_metadata.edge_coverage = current_trace
+            .edges
+            .iter()
+            .filter(|edge| {
+                [edge.from(), edge.to()].iter().all(|line| {
+                    sut_db
+                        .get_line_meta(*line)
+                        .is_some_and(|line_meta| !line_meta.is_comment)
+                })
+            })
+            .copied()
+            .collect();
+        for function_id in input_metadata.function_coverage.iter() {
+            sut_db.set_function_covered(*function_id, fuzzer_configuration_id);
+        }
 
-        self.input_id_to_trace_map
-            .insert(input_id, current_trace.clone());
-
-        self.fuzzer_id_input_id_map
-            .entry(fuzzer_configuration_id)
-            .or_default()
-            .push(input_id);
+        self.input_id_to_trace_map.insert(input_id, current_trace);
         self.fuzzer_input_id_to_input_id_map.insert(
             (fuzzer_configuration_id, input_metadata.fuzz_input_id),
             input_id,
         );
-        self.input_id_to_input_meta_map
-            .insert(input_id, input_metadata);
 
-        sut_db
+        if input_metadata.is_initial_seed {
+            self.fuzzer_id_initial_seeds_map
+                .entry(fuzzer_configuration_id)
+                .or_default()
+                .push(input_id);
+            self.initial_seeds_input_meta_map
+                .entry(fuzzer_configuration_id)
+                .or_default()
+                .insert(input_id, input_metadata);
+        } else {
+            self.fuzzer_id_input_id_map
+                .entry(fuzzer_configuration_id)
+                .or_default()
+                .push(input_id);
+            self.input_id_to_input_meta_map
+                .insert(input_id, input_metadata);
+        }
+
+        Ok(())
     }
 
-    pub fn post_process(&mut self) {
+    pub fn get_skipped_inputs(&self) -> &Vec<SkippedInput> {
+        &self.skipped_inputs
+    }
+
+    pub fn get_ingestion_report(&self, fuzzer_configuration_id: &u32) -> Option<&IngestionReport> {
+        self.ingestion_reports.get(fuzzer_configuration_id)
+    }
+
+    /// Lists the new inputs whose parents were not ingested in the ingestion reports and drops
+    /// the parents that arrived since from the inputs listed before. Returns the inputs that found
+    /// a parent that way.
+    fn find_orphaned_inputs(&mut self, new_input_ids: &[InputId]) -> Vec<InputId> {
+        let input_ids = &self.fuzzer_input_id_to_input_id_map;
+        let mut adopted_input_ids = Vec::new();
+        for (fuzzer_configuration_id, report) in self.ingestion_reports.iter_mut() {
+            report.orphaned_inputs.retain_mut(|orphaned_input| {
+                let num_missing_parents = orphaned_input.missing_parents.len();
+                orphaned_input
+                    .missing_parents
+                    .retain(|parent| !input_ids.contains_key(&(*fuzzer_configuration_id, *parent)));
+                if orphaned_input.missing_parents.len() < num_missing_parents {
+                    adopted_input_ids.extend(
+                        input_ids.get(&(*fuzzer_configuration_id, orphaned_input.fuzz_input_id)),
+                    );
+                }
+                !orphaned_input.missing_parents.is_empty()
+            });
+        }
+
+        for input_id in new_input_ids {
+            let input_meta = &self.input_id_to_input_meta_map[input_id];
+            let missing_parents: Vec<u32> = input_meta
+                .parents
+                .iter()
+                .filter(|parent| {
+                    !self
+                        .fuzzer_input_id_to_input_id_map
+                        .contains_key(&(input_meta.fuzzer_configuration, **parent))
+                })
+                .copied()
+                .collect();
+            if !missing_parents.is_empty() {
+                self.ingestion_reports
+                    .entry(input_meta.fuzzer_configuration)
+                    .or_default()
+                    .orphaned_inputs
+                    .push(OrphanedInput {
+                        fuzz_input_id: input_meta.fuzz_input_id,
+                        file_name_stem: input_meta.file_name_stem.clone(),
+                        missing_parents,
+                    });
+            }
+        }
+        adopted_input_ids
+    }
+
+    /// `input_ids` and all inputs descending from them
+    fn get_descendants_of(&self, input_ids: &[InputId]) -> HashSet<InputId> {
+        let mut descendants: HashSet<InputId> = HashSet::new();
+        if input_ids.is_empty() {
+            return descendants;
+        }
+
+        let mut children: HashMap<(u32, u32), Vec<InputId>> = HashMap::new();
         for (input_id, input_meta) in self.input_id_to_input_meta_map.iter() {
-            let parents =
-                self.get_initial_seed_parents_for(input_id, &input_meta.fuzzer_configuration);
-            for parent in parents.iter() {
-                let parent_meta = self
-                    .initial_seeds_input_meta_map
-                    .get(&input_meta.fuzzer_configuration)
-                    .unwrap()
-                    .get(parent)
-                    .unwrap();
-                self.fuzzer_id_initial_seeds_id_to_children_input_id_map
-                    .entry((input_meta.fuzzer_configuration, parent_meta.fuzz_input_id))
+            for parent in input_meta.parents.iter() {
+                children
+                    .entry((input_meta.fuzzer_configuration, *parent))
                     .or_default()
                     .push(*input_id);
             }
         }
+        let mut unvisited = input_ids.to_vec();
+        while let Some(input_id) = unvisited.pop() {
+            if !descendants.insert(input_id) {
+                continue;
+            }
+            let input_meta = &self.input_id_to_input_meta_map[&input_id];
+            if let Some(input_ids) =
+                children.get(&(input_meta.fuzzer_configuration, input_meta.fuzz_input_id))
+            {
+                unvisited.extend(input_ids);
+            }
+        }
+        descendants
+    }
+
+    fn link_to_initial_seeds(&mut self, input_id: InputId) {
+        let fuzzer_configuration_id =
+            self.input_id_to_input_meta_map[&input_id].fuzzer_configuration;
+        for parent in self.get_initial_seed_parents_for(&input_id, &fuzzer_configuration_id) {
+            let initial_seed_id =
+                self.initial_seeds_input_meta_map[&fuzzer_configuration_id][&parent].fuzz_input_id;
+            let children = self
+                .fuzzer_id_initial_seeds_id_to_children_input_id_map
+                .entry((fuzzer_configuration_id, initial_seed_id))
+                .or_default();
+            // an input linked before may already reach the initial seed through another parent
+            if let Err(index) =
+                children.binary_search_by_key(&input_id.as_usize(), InputId::as_usize)
+            {
+                children.insert(index, input_id);
+            }
+        }
     }
 
-    fn get_raw_bytes_for_input(&self, fuzzer_config: &FuzzerConfig, file_stem: &str) -> Vec<u8> {
-        let mut buffer: Vec<u8> = Vec::new();
-        let file_path =
-            Path::new(&fuzzer_config.inputs_directory_path).join(file_stem.replace("::", ","));
-        let _ = File::open(file_path).unwrap().read_to_end(&mut buffer);
-        buffer
+    /// Links the inputs added since the last call to their initial seeds. A parent may arrive
+    /// after its child, so the inputs that were missing one of the new inputs as a parent are
+    /// linked again, along with their descendants.
+    pub fn post_process(&mut self) {
+        let num_inputs = self.input_id_to_trace_map.len();
+        let new_input_ids: Vec<InputId> = (self.num_linked_inputs + 1..=num_inputs)
+            .map(InputId::new)
+            .filter(|input_id| self.input_id_to_input_meta_map.contains_key(input_id))
+            .collect();
+        self.num_linked_inputs = num_inputs;
+
+        let adopted_input_ids = self.find_orphaned_inputs(&new_input_ids);
+        let mut input_ids: Vec<InputId> = self
+            .get_descendants_of(&adopted_input_ids)
+            .into_iter()
+            .chain(new_input_ids)
+            .collect();
+        input_ids.sort_by_key(InputId::as_usize);
+        input_ids.dedup();
+        for input_id in input_ids {
+            self.link_to_initial_seeds(input_id);
+        }
     }
 
+    fn get_raw_bytes_for_input(
+        &self,
+        fuzzer_config: &FuzzerConfig,
+        input_meta: &InputMeta,
+    ) -> Result<Vec<u8>, CompareError> {
+        let file_name = input_meta.file_name_stem.replace("::", ",");
+        // libFuzzer and Honggfuzz read their initial seeds from a directory of their own
+        let file_path = match &fuzzer_config.queue_name_format {
+            QueueNameFormat::LibFuzzer {
+                seed_corpus_path, ..
+            }
+            | QueueNameFormat::HonggfuzzOutput {
+                seed_corpus_path, ..
+            } if input_meta.is_initial_seed => Path::new(seed_corpus_path).join(file_name),
+            _ => Path::new(&fuzzer_config.inputs_directory_path).join(file_name),
+        };
+        fs::read(&file_path).map_err(|e| CompareError::UnreadableInput {
+            path: file_path.to_string_lossy().into_owned(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Counts for each byte offset how often it changes between the initial seed and the inputs
+    /// descending from it, in the order they were found.
     pub fn compare_inputs(
         &self,
         configuration_id: &u32,
         initial_seed_id: &u32,
-    ) -> HashMap<usize, u32> {
+    ) -> Result<HashMap<usize, u32>, CompareError> {
         let mut previous_byte_values: HashMap<usize, u8> = HashMap::new();
         let mut ret: HashMap<usize, u32> = HashMap::new();
-        let fuzzer_config = self.fuzzer_configurations.get(configuration_id).unwrap();
-        let initial_seed_input_id = self
+        let fuzzer_config = self
+            .fuzzer_configurations
+            .get(configuration_id)
+            .ok_or(CompareError::UnknownFuzzerConfiguration(*configuration_id))?;
+        let current_initial_seed_meta = self
             .fuzzer_input_id_to_input_id_map
             .get(&(*configuration_id, *initial_seed_id))
-            .unwrap();
-        let current_initial_seed_meta = self
-            .initial_seeds_input_meta_map
-            .get(configuration_id)
-            .unwrap()
-            .get(initial_seed_input_id)
-            .unwrap();
+            .and_then(|input_id| {
+                self.initial_seeds_input_meta_map
+                    .get(configuration_id)?
+                    .get(input_id)
+            })
+            .ok_or(CompareError::UnknownInitialSeed(*initial_seed_id))?;
         let initial_seed_raw_bytes =
-            self.get_raw_bytes_for_input(fuzzer_config, &current_initial_seed_meta.file_name_stem);
+            self.get_raw_bytes_for_input(fuzzer_config, current_initial_seed_meta)?;
         for (index, raw_value) in initial_seed_raw_bytes.iter().enumerate() {
             previous_byte_values.insert(index, *raw_value);
             ret.insert(index, 0);
         }
 
+        // an initial seed without children is compared with nothing
         let corresponding_input_ids = self
             .fuzzer_id_initial_seeds_id_to_children_input_id_map
             .get(&(*configuration_id, *initial_seed_id))
-            .unwrap();
+            .map_or(&[][..], Vec::as_slice);
         for input_id in corresponding_input_ids {
-            let current_input_meta = self.input_id_to_input_meta_map.get(input_id).unwrap();
+            let current_input_meta = &self.input_id_to_input_meta_map[input_id];
             let current_seed_raw_bytes =
-                self.get_raw_bytes_for_input(fuzzer_config, &current_input_meta.file_name_stem);
+                self.get_raw_bytes_for_input(fuzzer_config, current_input_meta)?;
 
             for (index, raw_value) in current_seed_raw_bytes.iter().enumerate() {
                 if previous_byte_values.contains_key(&index) {
@@ -331,7 +880,7 @@ impl InputsDatabase {
             }
         }
 
-        ret
+        Ok(ret)
     }
 
     pub fn get_fuzzer_configuration(&self, configuration_id: &u32) -> Option<&FuzzerConfig> {
@@ -345,13 +894,33 @@ impl InputsDatabase {
     pub fn get_all_initial_seeds_for_fuzzer_id(&self, fuzzer_id: &u32) -> Vec<&InputId> {
         self.initial_seeds_input_meta_map
             .get(fuzzer_id)
-            .unwrap()
-            .keys()
+            .into_iter()
+            .flat_map(|initial_seeds| initial_seeds.keys())
+            .collect()
+    }
+
+    pub fn get_all_inputs_for_fuzzer_id(&self, fuzzer_id: &u32) -> &[InputId] {
+        self.fuzzer_id_input_id_map
+            .get(fuzzer_id)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the initial seeds and all other inputs of a fuzzer configuration, if any
+    pub fn get_all_input_ids_with_initial_seeds_for(&self, fuzzer_id: &u32) -> Vec<InputId> {
+        let initial_seeds = self.fuzzer_id_initial_seeds_map.get(fuzzer_id);
+        let inputs = self.fuzzer_id_input_id_map.get(fuzzer_id);
+        initial_seeds
+            .into_iter()
+            .chain(inputs)
+            .flatten()
+            .copied()
             .collect()
     }
 
-    pub fn get_all_inputs_for_fuzzer_id(&self, fuzzer_id: &u32) -> &Vec<InputId> {
-        self.fuzzer_id_input_id_map.get(fuzzer_id).unwrap()
+    /// Number of inputs including the initial seeds, their ids are 1 to this number in the order
+    /// they were added
+    pub fn get_num_inputs(&self) -> usize {
+        self.input_id_to_trace_map.len()
     }
 
     pub fn get_all_inputs_meta_info(&self) -> &HashMap<InputId, InputMeta> {
@@ -368,13 +937,148 @@ impl InputsDatabase {
             .unwrap()
     }
 
+    /// Like `get_input_id_for`, but for ids that may not exist, e.g. from a request
+    pub fn find_input_id_for(
+        &self,
+        fuzzer_configuration_id: &u32,
+        fuzz_input_id: &u32,
+    ) -> Option<&InputId> {
+        self.fuzzer_input_id_to_input_id_map
+            .get(&(*fuzzer_configuration_id, *fuzz_input_id))
+    }
+
+    /// Returns the meta information of an input or initial seed
+    pub fn find_input_meta_for(&self, input_id: &InputId) -> Option<&InputMeta> {
+        self.input_id_to_input_meta_map.get(input_id).or_else(|| {
+            self.initial_seeds_input_meta_map
+                .values()
+                .find_map(|initial_seeds| initial_seeds.get(input_id))
+        })
+    }
+
+    /// Sums the execution counts of the covered lines of `input_ids`. Inputs whose coverage
+    /// backend records no execution counts count once for each line they cover.
+    pub fn get_line_hit_counts_for(&self, input_ids: &[InputId]) -> HashMap<LineId, u64> {
+        let mut line_hit_counts: HashMap<LineId, u64> = HashMap::new();
+        for input_id in input_ids {
+            let (Some(input_meta), Some(trace)) = (
+                self.find_input_meta_for(input_id),
+                self.input_id_to_trace_map.get(input_id),
+            ) else {
+                continue;
+            };
+            for line in input_meta.source_line_coverage.iter() {
+                *line_hit_counts.entry(*line).or_default() +=
+                    trace.line_hit_counts.get(line).copied().unwrap_or(1);
+            }
+        }
+        line_hit_counts
+    }
+
+    /// Whether the coverage backend recorded how often the input executed each of its lines
+    pub fn has_execution_counts(&self, input_id: &InputId) -> bool {
+        self.input_id_to_trace_map
+            .get(input_id)
+            .is_some_and(|trace| !trace.line_hit_counts.is_empty())
+    }
+
+    /// Returns how often `input_ids` executed each line of `file_id` that any of them covers,
+    /// ordered by line. Inputs without execution counts execute each of their lines once.
+    pub fn get_line_heat_for_file_id(
+        &self,
+        input_ids: &[InputId],
+        file_id: &FileId,
+    ) -> Vec<LineHeat> {
+        let mut lines: HashMap<u32, LineHeat> = HashMap::new();
+        for input_id in input_ids {
+            let (Some(input_meta), Some(trace)) = (
+                self.find_input_meta_for(input_id),
+                self.input_id_to_trace_map.get(input_id),
+            ) else {
+                continue;
+            };
+            for line in input_meta.source_line_coverage.iter() {
+                if line.file() != *file_id {
+                    continue;
+                }
+                let executions = trace.line_hit_counts.get(line).copied().unwrap_or(1);
+                let line_heat = lines.entry(line.num()).or_insert(LineHeat {
+                    line: line.num(),
+                    executions: 0,
+                    inputs: 0,
+                    max_executions: 0,
+                });
+                line_heat.executions += executions;
+                line_heat.inputs += 1;
+                line_heat.max_executions = line_heat.max_executions.max(executions);
+            }
+        }
+
+        let mut line_heat: Vec<LineHeat> = lines.into_values().collect();
+        line_heat.sort_by_key(|line_heat| line_heat.line);
+        line_heat
+    }
+
+    /// Returns which inputs of a fuzzer configuration reached a function, and from which initial
+    /// seeds the first of them descends
+    pub fn get_function_coverage_for(
+        &self,
+        fuzzer_configuration_id: &u32,
+        function_id: &FunctionId,
+    ) -> FunctionCoverage {
+        let mut function_coverage = FunctionCoverage::default();
+        if let Some(initial_seeds) = self
+            .initial_seeds_input_meta_map
+            .get(fuzzer_configuration_id)
+        {
+            function_coverage.initial_seeds = initial_seeds
+                .values()
+                .filter(|input_meta| input_meta.function_coverage.contains(function_id))
+                .map(|input_meta| input_meta.fuzz_input_id)
+                .collect();
+            function_coverage.initial_seeds.sort();
+        }
+
+        let mut inputs: Vec<&InputMeta> = self
+            .fuzzer_id_input_id_map
+            .get(fuzzer_configuration_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|input_id| self.input_id_to_input_meta_map.get(input_id))
+            .filter(|input_meta| input_meta.function_coverage.contains(function_id))
+            .collect();
+        inputs.sort_by_key(|input_meta| (input_meta.executed_on, input_meta.fuzz_input_id));
+        function_coverage.first_executed_on =
+            inputs.first().map(|input_meta| input_meta.executed_on);
+        // some fuzzers record no initial seeds at all
+        if let (Some(first_input), Some(initial_seeds)) = (
+            inputs.first(),
+            self.initial_seeds_input_meta_map
+                .get(fuzzer_configuration_id),
+        ) {
+            function_coverage.first_input_initial_seeds = self
+                .get_initial_seed_parents_for(&first_input.id, fuzzer_configuration_id)
+                .iter()
+                .filter_map(|input_id| initial_seeds.get(input_id))
+                .map(|input_meta| input_meta.fuzz_input_id)
+                .collect();
+            function_coverage.first_input_initial_seeds.sort();
+        }
+        function_coverage.inputs = inputs
+            .iter()
+            .map(|input_meta| input_meta.fuzz_input_id)
+            .collect();
+
+        function_coverage
+    }
+
+    /// Returns the initial seeds of a fuzzer configuration added with `add_fuzzer_configuration`,
+    /// which may be none
     pub fn get_all_initial_seeds_meta_info(
         &self,
         fuzzer_configuration_id: &u32,
     ) -> &HashMap<InputId, InputMeta> {
-        self.initial_seeds_input_meta_map
-            .get(fuzzer_configuration_id)
-            .unwrap()
+        &self.initial_seeds_input_meta_map[fuzzer_configuration_id]
     }
 
     pub fn get_trace_for(&self, input_id: &InputId) -> &Trace {
@@ -397,10 +1101,13 @@ impl InputsDatabase {
         let current_input_meta = self.input_id_to_input_meta_map.get(input_id).unwrap();
         if !current_input_meta.parents.is_empty() {
             for parent in current_input_meta.parents.iter() {
-                let parent_input_id = self
+                // the parent may have been skipped during ingestion
+                let Some(parent_input_id) = self
                     .fuzzer_input_id_to_input_id_map
                     .get(&(*fuzzer_configuration_id, *parent))
-                    .unwrap();
+                else {
+                    continue;
+                };
                 if self
                     .input_id_to_input_meta_map
                     .contains_key(parent_input_id)
@@ -524,8 +1231,69 @@ impl InputsDatabase {
         line_coverage
     }
 
-    pub fn get_run_times_for_fuzzer_id(&self, fuzzer_configuration_id: &u32) -> &(i64, i64) {
-        self.min_max_times.get(fuzzer_configuration_id).unwrap()
+    /// Returns the edges within `file_id` taken by an initial seed and the inputs descending from
+    /// it, ordered by line
+    pub fn get_initial_seed_edge_coverage_for_file_id(
+        &self,
+        fuzzer_configuration_id: &u32,
+        initial_seed_id: &u32,
+        file_id: &FileId,
+    ) -> Vec<EdgeCoverage> {
+        let Some(initial_seed_meta) = self
+            .initial_seeds_input_meta_map
+            .get(fuzzer_configuration_id)
+            .and_then(|initial_seeds| {
+                initial_seeds
+                    .values()
+                    .find(|input_meta| input_meta.fuzz_input_id == *initial_seed_id)
+            })
+        else {
+            return Vec::new();
+        };
+
+        // (initial seed takes it, number of descendants taking it)
+        let mut edges: HashMap<EdgeId, (bool, u32)> = HashMap::new();
+        for edge in initial_seed_meta.edge_coverage.iter() {
+            if edge.file() == *file_id {
+                edges.entry(*edge).or_default().0 = true;
+            }
+        }
+        if self.has_children_for(fuzzer_configuration_id, initial_seed_id) {
+            for child in self
+                .get_all_children_input_ids_for(fuzzer_configuration_id, &vec![*initial_seed_id])
+                .iter()
+            {
+                let Some(child_meta) = self.input_id_to_input_meta_map.get(child) else {
+                    continue;
+                };
+                for edge in child_meta.edge_coverage.iter() {
+                    if edge.file() == *file_id {
+                        edges.entry(*edge).or_default().1 += 1;
+                    }
+                }
+            }
+        }
+
+        let mut edge_coverage: Vec<EdgeCoverage> = edges
+            .into_iter()
+            .map(|(edge, (initial_seed, descendants))| EdgeCoverage {
+                from_line: edge.from().num(),
+                to_line: edge.to().num(),
+                initial_seed,
+                descendants,
+            })
+            .collect();
+        edge_coverage.sort_by_key(|edge| (edge.from_line, edge.to_line));
+        edge_coverage
+    }
+
+    /// Returns the first and last execution time of the inputs of a fuzzer configuration, both 0
+    /// if it has none
+    pub fn get_run_times_for_fuzzer_id(&self, fuzzer_configuration_id: &u32) -> (i64, i64) {
+        self.min_max_times
+            .get(fuzzer_configuration_id)
+            .copied()
+            .unwrap_or_default()
     }
 }
 
@@ -534,6 +1302,7 @@ mod tests {
     use std::env;
 
     use super::*;
+    use config::{CoverageBackend, QueueNameFormat};
 
     #[test]
     fn test_fuzzer_config_1() {
@@ -542,6 +1311,7 @@ mod tests {
             traces_directory_path: "Hello".to_string(),
             inputs_directory_path: "Hello".to_string(),
             fuzzer_configuration: "World".to_string(),
+            queue_name_format: QueueNameFormat::default(),
         };
         let mut input_db = InputsDatabase::new();
         input_db.add_fuzzer_configuration(&config);
@@ -555,6 +1325,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_find_orphaned_inputs() {
+        let mut input_db = InputsDatabase::new();
+        let mut input_meta = PatchedAflNameParser
+            .parse(
+                "traces/cycle:1::id:000007::executed_on:1753701941117::src:000001+000003.trace",
+                0,
+            )
+            .unwrap();
+        input_meta.id = InputId::new(1);
+        input_db
+            .fuzzer_input_id_to_input_id_map
+            .insert((0, 1), InputId::new(2));
+        input_db
+            .input_id_to_input_meta_map
+            .insert(input_meta.id, input_meta);
+
+        assert!(input_db.find_orphaned_inputs(&[InputId::new(1)]).is_empty());
+        assert!(input_db.find_orphaned_inputs(&[]).is_empty());
+        let orphaned_inputs = &input_db.get_ingestion_report(&0).unwrap().orphaned_inputs;
+        assert_eq!(orphaned_inputs.len(), 1);
+        assert_eq!(orphaned_inputs[0].fuzz_input_id, 7);
+        assert_eq!(orphaned_inputs[0].missing_parents, vec![3]);
+
+        // the missing parent arrives later
+        input_db
+            .fuzzer_input_id_to_input_id_map
+            .insert((0, 7), InputId::new(1));
+        input_db
+            .fuzzer_input_id_to_input_id_map
+            .insert((0, 3), InputId::new(3));
+        assert_eq!(input_db.find_orphaned_inputs(&[]), vec![InputId::new(1)]);
+        assert!(input_db
+            .get_ingestion_report(&0)
+            .unwrap()
+            .orphaned_inputs
+            .is_empty());
+    }
+
     #[test]
     fn test_input_1() {
         let path = env::current_dir().unwrap();
@@ -579,12 +1388,14 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
 
         let mut input_db = InputsDatabase::new();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let _sut_db = input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, sut_db, 0);
+        input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, &mut sut_db, 0).unwrap();
 
         assert_eq!(input_db.input_id_to_input_meta_map.len(), 1);
         assert_eq!(
@@ -621,12 +1432,14 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
 
         let mut input_db = InputsDatabase::new();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let sut_db = input_db.add_input(test_data_dir.join("traces/cycle:2::id:000005::executed_on:1753701941381::src:000002::time:455::execs:880::op:havoc::rep:1::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(), &config, sut_db, 0);
+        input_db.add_input(test_data_dir.join("traces/cycle:2::id:000005::executed_on:1753701941381::src:000002::time:455::execs:880::op:havoc::rep:1::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(), &config, &mut sut_db, 0).unwrap();
         println!("{:?}", sut_db);
 
         let mut file_meta_gt = FileMeta::new("test_data/test1/sources/main.c");
@@ -678,13 +1491,15 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
 
         let mut input_db = InputsDatabase::new();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let sut_db = input_db.add_input(test_data_dir.join("traces/cycle:2::id:000003::executed_on:1753701941262::src:000002::time:336::execs:656::op:havoc::rep:1::+cov::gain:2::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, sut_db, 0);
-        let _sut_db = input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, sut_db, 0);
+        input_db.add_input(test_data_dir.join("traces/cycle:2::id:000003::executed_on:1753701941262::src:000002::time:336::execs:656::op:havoc::rep:1::+cov::gain:2::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, &mut sut_db, 0).unwrap();
+        input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, &mut sut_db, 0).unwrap();
 
         let input_meta = input_db
             .get_all_inputs_meta_info()
@@ -717,21 +1532,24 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
 
         let mut input_db = InputsDatabase::new();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let _sut_db = input_db.add_initial_seed(
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000000::time:0::executed_on:1753701940885::execs:0::orig:a.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
+        )
+        .unwrap();
 
         let input_meta = input_db.get_all_initial_seeds_meta_info(&0);
 
@@ -776,31 +1594,35 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
 
         let mut input_db = InputsDatabase::new();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let sut_db = input_db.add_initial_seed(
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000000::time:0::executed_on:1753701940885::execs:0::orig:a.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
-        let _sut_db = input_db.add_initial_seed(
+        )
+        .unwrap();
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000001::time:0::executed_on:1753701940885::execs:0::orig:b.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
+        )
+        .unwrap();
 
         let input_meta = input_db.get_all_initial_seeds_meta_info(&0);
 
@@ -841,33 +1663,37 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
 
         let mut input_db = InputsDatabase::new();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let sut_db = input_db.add_initial_seed(
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000000::time:0::executed_on:1753701940885::execs:0::orig:a.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
-        let sut_db = input_db.add_initial_seed(
+        )
+        .unwrap();
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000001::time:0::executed_on:1753701940885::execs:0::orig:b.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
-        let sut_db = input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, sut_db, 0);
-        let _sut_db = input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, sut_db, 0);
+        )
+        .unwrap();
+        input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, &mut sut_db, 0).unwrap();
+        input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, &mut sut_db, 0).unwrap();
 
         assert_eq!(input_db.get_all_initial_seeds_meta_info(&0).len(), 2);
         let mut check_parent = input_db.get_initial_seed_parents_for(&InputId::new(4), &0);
@@ -903,33 +1729,37 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
 
         let mut input_db = InputsDatabase::new();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let sut_db = input_db.add_initial_seed(
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000000::time:0::executed_on:1753701940885::execs:0::orig:a.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
-        let sut_db = input_db.add_initial_seed(
+        )
+        .unwrap();
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000001::time:0::executed_on:1753701940885::execs:0::orig:b.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
-        let sut_db = input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, sut_db, 0);
-        let _sut_db = input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, sut_db, 0);
+        )
+        .unwrap();
+        input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, &mut sut_db, 0).unwrap();
+        input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, &mut sut_db, 0).unwrap();
         input_db.post_process();
 
         println!(
@@ -964,33 +1794,37 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
 
         let mut input_db = InputsDatabase::new();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let sut_db = input_db.add_initial_seed(
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000000::time:0::executed_on:1753701940885::execs:0::orig:a.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
-        let sut_db = input_db.add_initial_seed(
+        )
+        .unwrap();
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000001::time:0::executed_on:1753701940885::execs:0::orig:b.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
-        let sut_db = input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(), &config, sut_db, 0);
-        let _sut_db = input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(), &config, sut_db, 0);
+        )
+        .unwrap();
+        input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(), &config, &mut sut_db, 0).unwrap();
+        input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(), &config, &mut sut_db, 0).unwrap();
 
         assert_eq!(input_db.get_all_initial_seeds_meta_info(&0).len(), 2);
         let check_parent = input_db.get_initial_seed_parents_for(&InputId::new(1), &0);
@@ -1029,6 +1863,7 @@ mod tests {
                 .unwrap(),
             ),
             fuzzer_configuration_id: 0,
+            queue_name_format: QueueNameFormat::default(),
         };
 
         let config = TargetConfig {
@@ -1046,6 +1881,10 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+
+            coverage_backend: CoverageBackend::DrCov,
+
+            additional_modules: vec![],
         };
 
         let mut input_db = InputsDatabase::new();
@@ -1053,28 +1892,30 @@ mod tests {
 
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let sut_db = input_db.add_initial_seed(
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000000::time:0::executed_on:1753701940885::execs:0::orig:a.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
-        let sut_db = input_db.add_initial_seed(
+        )
+        .unwrap();
+        input_db.add_input(
             test_data_dir
                 .join("traces/id:000001::time:0::executed_on:1753701940885::execs:0::orig:b.trace")
                 .to_string_lossy()
                 .into_owned()
                 .as_str(),
             &config,
-            sut_db,
+            &mut sut_db,
             0,
-        );
-        let sut_db = input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(), &config, sut_db, 0);
-        let _sut_db = input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, sut_db, 0);
+        )
+        .unwrap();
+        input_db.add_input(test_data_dir.join("traces/cycle:1::id:000002::executed_on:1753701941117::src:000001::time:191::execs:378::op:havoc::rep:2::+cov::gain:2::score:100::depth:1::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(), &config, &mut sut_db, 0).unwrap();
+        input_db.add_input(test_data_dir.join("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace").to_string_lossy().into_owned().as_str(),  &config, &mut sut_db, 0).unwrap();
         input_db.post_process();
 
         println!(
@@ -1090,8 +1931,445 @@ mod tests {
         ground_truth.insert(5, 1);
         ground_truth.insert(6, 1);
         ground_truth.insert(7, 1);
-        let byte_changes = input_db.compare_inputs(&0, &1);
+        let byte_changes = input_db.compare_inputs(&0, &1).unwrap();
         println!("byte changes: {:?}", byte_changes);
         assert_eq!(byte_changes, ground_truth);
     }
+
+    #[test]
+    fn test_empty_seed_corpus() {
+        let test_data_dir = fs::canonicalize("../test_data").unwrap();
+        let directory = env::temp_dir().join("seedui_test_empty_seed_corpus");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(directory.join("seeds")).unwrap();
+        fs::create_dir_all(directory.join("traces")).unwrap();
+        // the coverage of the two libFuzzer corpus entries
+        let mut file_names = Vec::new();
+        for (trace, corpus_entry) in lcov_target_traces().iter().zip([
+            "3f786850e387550fdab836ed7e6dc881de23001b",
+            "89e6c98d92887913cadf06b2adb97f26cde4849b",
+        ]) {
+            let file_name = directory.join(format!("traces/{}.info", corpus_entry));
+            fs::copy(trace, &file_name).unwrap();
+            file_names.push(file_name.to_string_lossy().into_owned());
+        }
+
+        let config = lcov_target_config();
+        let fuzzer_config = FuzzerConfig {
+            fuzzer_configuration: "libfuzzer".to_string(),
+            traces_directory_path: directory.join("traces").to_string_lossy().into_owned(),
+            inputs_directory_path: test_data_dir
+                .join("libfuzzer/corpus")
+                .to_string_lossy()
+                .into_owned(),
+            fuzzer_configuration_id: 0,
+            queue_name_format: QueueNameFormat::LibFuzzer {
+                log_path: test_data_dir
+                    .join("libfuzzer/fuzz.log")
+                    .to_string_lossy()
+                    .into_owned(),
+                seed_corpus_path: directory.join("seeds").to_string_lossy().into_owned(),
+            },
+        };
+        let mut input_db = InputsDatabase::new();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+        input_db.add_fuzzer_configuration(&fuzzer_config);
+        input_db
+            .add_inputs(&file_names, &config, &mut sut_db, 0, 2)
+            .unwrap();
+        input_db.post_process();
+        let _ = fs::remove_dir_all(&directory);
+
+        assert!(input_db.get_skipped_inputs().is_empty());
+        assert!(input_db.get_all_initial_seeds_for_fuzzer_id(&0).is_empty());
+        assert!(input_db.get_all_initial_seeds_meta_info(&0).is_empty());
+        assert_eq!(input_db.get_all_inputs_for_fuzzer_id(&0).len(), 2);
+        for input_id in input_db.get_all_inputs_for_fuzzer_id(&0) {
+            assert!(input_db
+                .get_initial_seed_parents_for(input_id, &0)
+                .is_empty());
+        }
+
+        // a fuzzer configuration without any traces yet
+        input_db.add_fuzzer_configuration(&FuzzerConfig {
+            fuzzer_configuration_id: 1,
+            ..fuzzer_config
+        });
+        assert!(input_db.get_all_initial_seeds_meta_info(&1).is_empty());
+        assert!(input_db.get_all_inputs_for_fuzzer_id(&1).is_empty());
+        assert_eq!(input_db.get_run_times_for_fuzzer_id(&1), (0, 0));
+    }
+
+    /// Target configuration of `test_data/lcov_target`, which holds the LCOV tracefiles of an
+    /// initial seed and of one input derived from it
+    fn lcov_target_config() -> TargetConfig {
+        let sources_dir = fs::canonicalize("../test_data/lcov_target/sources").unwrap();
+        TargetConfig {
+            // no debug information is read for LCOV tracefiles
+            target_path: sources_dir.to_string_lossy().into_owned(),
+            target_source_code_path: sources_dir.to_string_lossy().into_owned(),
+            coverage_backend: CoverageBackend::Lcov,
+            ..TargetConfig::new()
+        }
+    }
+
+    /// The trace files of `test_data/lcov_target`, the initial seed first
+    fn lcov_target_traces() -> Vec<String> {
+        let mut file_names: Vec<String> = fs::read_dir("../test_data/lcov_target/traces")
+            .unwrap()
+            .map(|e| fs::canonicalize(e.unwrap().path()).unwrap())
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        file_names.sort();
+        file_names
+    }
+
+    /// All inputs of `test_data/lcov_target` added for fuzzer configuration 0
+    fn lcov_target_db() -> (InputsDatabase, SUT) {
+        let config = lcov_target_config();
+        let mut input_db = InputsDatabase::new();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+        input_db
+            .add_inputs(&lcov_target_traces(), &config, &mut sut_db, 0, 2)
+            .unwrap();
+        input_db.post_process();
+        (input_db, sut_db)
+    }
+
+    fn lcov_target_file_id(sut_db: &SUT) -> FileId {
+        let config = lcov_target_config();
+        sut_db
+            .get_file_id(&format!("{}/test.c", config.target_source_code_path))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_line_hit_counts_lcov() {
+        let (input_db, sut_db) = lcov_target_db();
+        assert!(input_db.get_skipped_inputs().is_empty());
+
+        let initial_seed_id = *input_db.get_input_id_for(&0, &0);
+        let input_id = *input_db.get_input_id_for(&0, &1);
+        let file_id = lcov_target_file_id(&sut_db);
+        let line = |num: u32| LineId::new(file_id, num);
+
+        let initial_seed_counts = input_db.get_line_hit_counts_for(&[initial_seed_id]);
+        // the empty line 34 is no code, line 3 is not executed
+        assert_eq!(initial_seed_counts.len(), 9);
+        assert!(!initial_seed_counts.contains_key(&line(3)));
+
+        let line_hit_counts = input_db.get_line_hit_counts_for(&[initial_seed_id, input_id]);
+        assert_eq!(line_hit_counts[&line(22)], 2);
+        assert_eq!(line_hit_counts[&line(6)], 4);
+        assert_eq!(line_hit_counts[&line(3)], 2);
+        assert!(!line_hit_counts.contains_key(&line(34)));
+    }
+
+    #[test]
+    fn test_line_heat_lcov() {
+        let (input_db, sut_db) = lcov_target_db();
+        let initial_seed_id = *input_db.get_input_id_for(&0, &0);
+        let input_id = *input_db.get_input_id_for(&0, &1);
+        assert!(input_db.has_execution_counts(&initial_seed_id));
+        assert!(!input_db.has_execution_counts(&InputId::new(0)));
+        let file_id = lcov_target_file_id(&sut_db);
+
+        let line_heat = input_db.get_line_heat_for_file_id(&[initial_seed_id, input_id], &file_id);
+        let heat_of = |line: u32| line_heat.iter().find(|l| l.line == line).cloned();
+        // the loop of test runs for the child only
+        assert_eq!(
+            heat_of(6),
+            Some(LineHeat {
+                line: 6,
+                executions: 4,
+                inputs: 1,
+                max_executions: 4,
+            })
+        );
+        assert_eq!(
+            heat_of(22),
+            Some(LineHeat {
+                line: 22,
+                executions: 2,
+                inputs: 2,
+                max_executions: 1,
+            })
+        );
+        assert_eq!(heat_of(34), None);
+        assert!(line_heat.windows(2).all(|w| w[0].line < w[1].line));
+        assert!(input_db
+            .get_line_heat_for_file_id(&[initial_seed_id], &FileId::new(file_id.as_usize() + 1))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_function_coverage() {
+        let config = lcov_target_config();
+        let mut input_db = InputsDatabase::new();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+        // the functions are indexed before the inputs are added
+        let file_id = sut_db
+            .parse_file(&format!("{}/test.c", config.target_source_code_path))
+            .unwrap()
+            .unwrap();
+        let foo_id = sut_db.add_function("foo", file_id, 3, 3);
+        let main_id = sut_db.add_function("main", file_id, 22, 36);
+        input_db
+            .add_inputs(&lcov_target_traces(), &config, &mut sut_db, 0, 2)
+            .unwrap();
+
+        let initial_seed_meta = input_db.find_input_meta_for(input_db.get_input_id_for(&0, &0));
+        assert_eq!(
+            initial_seed_meta.unwrap().function_coverage,
+            HashSet::from([main_id])
+        );
+        assert_eq!(sut_db.get_function_meta(&main_id).unwrap().hit_count, 2);
+        assert_eq!(sut_db.get_function_meta(&foo_id).unwrap().hit_count, 1);
+
+        // only reached by the input derived from seed 0
+        assert_eq!(
+            input_db.get_function_coverage_for(&0, &foo_id),
+            FunctionCoverage {
+                initial_seeds: vec![],
+                inputs: vec![1],
+                first_executed_on: Some(1753701941117),
+                first_input_initial_seeds: vec![0],
+            }
+        );
+        assert_eq!(
+            input_db
+                .get_function_coverage_for(&0, &main_id)
+                .initial_seeds,
+            vec![0]
+        );
+        assert_eq!(
+            input_db.get_function_coverage_for(&1, &main_id),
+            FunctionCoverage::default()
+        );
+    }
+
+    #[test]
+    fn test_edge_coverage_lcov() {
+        let (input_db, sut_db) = lcov_target_db();
+        let file_id = lcov_target_file_id(&sut_db);
+        // LCOV records no order of execution, edges of drcov full traces are tested in trace_map
+        let child_id = *input_db.get_input_id_for(&0, &1);
+        assert!(input_db
+            .find_input_meta_for(&child_id)
+            .unwrap()
+            .edge_coverage
+            .is_empty());
+        assert!(input_db
+            .get_initial_seed_edge_coverage_for_file_id(&0, &0, &file_id)
+            .is_empty());
+        assert!(input_db
+            .get_initial_seed_edge_coverage_for_file_id(&0, &7, &file_id)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parent_after_child() {
+        let config = lcov_target_config();
+        let file_names = lcov_target_traces();
+        let mut input_db = InputsDatabase::new();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+
+        // the child is written before its initial seed
+        input_db
+            .add_inputs(&file_names[1..], &config, &mut sut_db, 0, 2)
+            .unwrap();
+        input_db.post_process();
+        let orphaned_inputs = &input_db.get_ingestion_report(&0).unwrap().orphaned_inputs;
+        assert_eq!(orphaned_inputs.len(), 1);
+        assert_eq!(orphaned_inputs[0].missing_parents, vec![0]);
+        assert!(!input_db.has_children_for(&0, &0));
+
+        input_db
+            .add_inputs(&file_names[..1], &config, &mut sut_db, 0, 2)
+            .unwrap();
+        input_db.post_process();
+        assert!(input_db
+            .get_ingestion_report(&0)
+            .unwrap()
+            .orphaned_inputs
+            .is_empty());
+        assert_eq!(
+            input_db.get_all_children_input_ids_for(&0, &vec![0]),
+            vec![*input_db.get_input_id_for(&0, &1)]
+        );
+    }
+
+    #[test]
+    fn test_add_inputs_incrementally() {
+        let config = lcov_target_config();
+        let file_names = lcov_target_traces();
+
+        let mut all_at_once = InputsDatabase::new();
+        let mut all_at_once_sut = SUT::new();
+        all_at_once_sut.parse_config(&config);
+        all_at_once
+            .add_inputs(&file_names, &config, &mut all_at_once_sut, 0, 2)
+            .unwrap();
+        all_at_once.post_process();
+
+        // the initial seed at startup, its child written by the running campaign later
+        let mut input_db = InputsDatabase::new();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+        input_db
+            .add_inputs(&file_names[..1], &config, &mut sut_db, 0, 2)
+            .unwrap();
+        input_db.post_process();
+        assert!(input_db.has_trace_file(0, &file_names[0]));
+        assert!(!input_db.has_trace_file(0, &file_names[1]));
+        assert!(!input_db.has_trace_file(1, &file_names[0]));
+        assert!(!input_db.has_children_for(&0, &0));
+
+        input_db
+            .add_inputs(&file_names[1..], &config, &mut sut_db, 0, 2)
+            .unwrap();
+        input_db.post_process();
+        // running post_process twice does not list children twice
+        input_db.post_process();
+        assert!(input_db.has_trace_file(0, &file_names[1]));
+        assert_eq!(
+            input_db.get_all_children_input_ids_for(&0, &vec![0]),
+            all_at_once.get_all_children_input_ids_for(&0, &vec![0])
+        );
+        assert_eq!(
+            input_db.get_run_times_for_fuzzer_id(&0),
+            all_at_once.get_run_times_for_fuzzer_id(&0)
+        );
+        assert_eq!(input_db.get_ingestion_report(&0).unwrap().traces_seen, 2);
+        for (file_id, file_meta) in all_at_once_sut.get_all_file_meta() {
+            assert_eq!(
+                sut_db.get_file_meta(file_id).unwrap().unique_line_hits,
+                file_meta.unique_line_hits
+            );
+        }
+    }
+
+    #[test]
+    fn test_add_input_skipped() {
+        let config = lcov_target_config();
+        let traces = lcov_target_traces();
+        let missing_trace = format!("{}.missing", traces[1]);
+        let mut input_db = InputsDatabase::new();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+
+        input_db
+            .add_input(&traces[0], &config, &mut sut_db, 0)
+            .unwrap();
+        // reported like a trace of add_inputs, also when it is retried
+        for _ in 0..2 {
+            assert!(input_db
+                .add_input(&missing_trace, &config, &mut sut_db, 0)
+                .is_err());
+        }
+        assert_eq!(input_db.get_skipped_inputs().len(), 1);
+        assert_eq!(input_db.get_skipped_inputs()[0].file_name, missing_trace);
+        assert!(input_db.has_trace_file(0, &traces[0]));
+        assert!(!input_db.has_trace_file(0, &missing_trace));
+        assert_eq!(input_db.get_ingestion_report(&0).unwrap().traces_seen, 2);
+    }
+
+    #[test]
+    fn test_skipped_trace_retried() {
+        let config = lcov_target_config();
+        let traces = lcov_target_traces();
+        let traces_dir = env::temp_dir().join("seedui_test_skipped_trace_retried");
+        let _ = fs::remove_dir_all(&traces_dir);
+        fs::create_dir_all(&traces_dir).unwrap();
+        let child_trace = traces_dir.join(Path::new(&traces[1]).file_name().unwrap());
+        let file_names = [
+            traces[0].clone(),
+            child_trace.to_string_lossy().into_owned(),
+        ];
+
+        let mut input_db = InputsDatabase::new();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+        // the child is listed before the tracer wrote it, retrying it still skips it once
+        for retried_file_names in [&file_names[..], &file_names[1..]] {
+            let resolved_inputs = input_db
+                .resolve_inputs(retried_file_names, &config, 0, 2)
+                .unwrap();
+            input_db.merge_inputs(resolved_inputs, &config, &mut sut_db);
+        }
+        assert_eq!(input_db.get_skipped_inputs().len(), 1);
+        assert_eq!(input_db.get_skipped_inputs()[0].file_name, file_names[1]);
+        assert!(!input_db.has_trace_file(0, &file_names[1]));
+
+        fs::copy(&traces[1], &child_trace).unwrap();
+        input_db
+            .add_inputs(&file_names[1..], &config, &mut sut_db, 0, 2)
+            .unwrap();
+        input_db.post_process();
+        assert!(input_db.get_skipped_inputs().is_empty());
+        assert!(input_db.has_trace_file(0, &file_names[1]));
+        assert!(input_db.has_children_for(&0, &0));
+        assert_eq!(input_db.get_ingestion_report(&0).unwrap().traces_seen, 2);
+        fs::remove_dir_all(&traces_dir).unwrap();
+    }
+
+    #[test]
+    fn test_compare_libfuzzer_initial_seed() {
+        let test_data_dir = fs::canonicalize("../test_data/libfuzzer").unwrap();
+        let directory = env::temp_dir().join("seedui_test_compare_libfuzzer_initial_seed");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        // the initial seed and a corpus entry, both with the coverage of the lcov_target seed
+        let traces = lcov_target_traces();
+        let file_names: Vec<String> = ["seed.txt", "3f786850e387550fdab836ed7e6dc881de23001b"]
+            .iter()
+            .map(|input| {
+                let file_name = directory.join(format!("{}.info", input));
+                fs::copy(&traces[0], &file_name).unwrap();
+                file_name.to_string_lossy().into_owned()
+            })
+            .collect();
+
+        let config = lcov_target_config();
+        let path = |name: &str| test_data_dir.join(name).to_string_lossy().into_owned();
+        let mut input_db = InputsDatabase::new();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+        input_db.add_fuzzer_configuration(&FuzzerConfig {
+            fuzzer_configuration: "libfuzzer".to_string(),
+            traces_directory_path: directory.to_string_lossy().into_owned(),
+            inputs_directory_path: path("corpus"),
+            fuzzer_configuration_id: 0,
+            queue_name_format: QueueNameFormat::LibFuzzer {
+                log_path: path("fuzz.log"),
+                seed_corpus_path: path("seeds"),
+            },
+        });
+        input_db
+            .add_inputs(&file_names, &config, &mut sut_db, 0, 2)
+            .unwrap();
+        input_db.post_process();
+        let _ = fs::remove_dir_all(&directory);
+        assert!(input_db.get_skipped_inputs().is_empty());
+
+        // read from the seed corpus, libFuzzer records no parents to compare it with
+        let byte_changes = input_db.compare_inputs(&0, &0).unwrap();
+        assert_eq!(
+            byte_changes.len(),
+            fs::read(path("seeds/seed.txt")).unwrap().len()
+        );
+        assert!(byte_changes.values().all(|changes| *changes == 0));
+        assert!(matches!(
+            input_db.compare_inputs(&0, &1),
+            Err(CompareError::UnknownInitialSeed(1))
+        ));
+        assert!(matches!(
+            input_db.compare_inputs(&1, &0),
+            Err(CompareError::UnknownFuzzerConfiguration(1))
+        ));
+    }
 }
@@ -0,0 +1,355 @@
+use std::{collections::HashMap, fs, path::Path, time::UNIX_EPOCH};
+
+use crate::{fuzzer_log_error, IngestionError};
+
+/// A NEW or REDUCE line of the libFuzzer log
+#[derive(Clone, Debug, PartialEq)]
+struct CorpusEvent {
+    runs: u64,
+    // milliseconds since the epoch, if the line is prefixed with a timestamp
+    timestamp: Option<i64>,
+    coverage: u32,
+    // name of the corpus file written for the new unit, only logged with -verbosity=2
+    file_name: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct FuzzerLog {
+    events: Vec<CorpusEvent>,
+    first_timestamp: Option<i64>,
+    initial_coverage: u32,
+    total_runs: Option<u64>,
+    total_seconds: Option<f64>,
+    exec_per_sec: Option<f64>,
+}
+
+// "1753701941.123456" (e.g. from `ts '%.s'`) in seconds or "1753701941123" in milliseconds
+fn parse_timestamp(token: &str) -> Option<i64> {
+    let value = token.parse::<f64>().ok()?;
+    if value > 1e11 {
+        Some(value as i64)
+    } else {
+        Some((value * 1000.0) as i64)
+    }
+}
+
+fn value_after<T: std::str::FromStr>(tokens: &[&str], name: &str) -> Option<T> {
+    let position = tokens.iter().position(|token| *token == name)?;
+    tokens.get(position + 1)?.parse::<T>().ok()
+}
+
+fn parse_log(log: &str) -> FuzzerLog {
+    let mut fuzzer_log = FuzzerLog::default();
+    for line in log.lines() {
+        let mut line = line.trim();
+        let mut timestamp = None;
+        if let Some((first, rest)) = line.split_once(char::is_whitespace) {
+            if let Some(parsed) = parse_timestamp(first) {
+                timestamp = Some(parsed);
+                line = rest.trim_start();
+                fuzzer_log.first_timestamp = fuzzer_log.first_timestamp.or(timestamp);
+            }
+        }
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if let Some(runs) = line.strip_prefix('#') {
+            // "#5	NEW    cov: 14 ft: 15 corp: 4/25b lim: 4 exec/s: 0 rss: 30Mb L: 5/10 MS: 1 ChangeByte-"
+            let Some(Ok(runs)) = runs.split_whitespace().next().map(|r| r.parse::<u64>()) else {
+                continue;
+            };
+            let coverage = value_after(&tokens, "cov:").unwrap_or_default();
+            match tokens.get(1) {
+                Some(&"NEW") | Some(&"REDUCE") => fuzzer_log.events.push(CorpusEvent {
+                    runs,
+                    timestamp,
+                    coverage,
+                    file_name: None,
+                }),
+                Some(&"INITED") => fuzzer_log.initial_coverage = coverage,
+                _ => {}
+            }
+        } else if let Some(written) = line.strip_prefix("Written ") {
+            // "Written 5 bytes to corpus/0b8a5f4..."
+            let Some((_, path)) = written.split_once(" to ") else {
+                continue;
+            };
+            let file_name = Path::new(path.trim())
+                .file_name()
+                .map(|f| f.to_string_lossy().into_owned());
+            if let Some(event) = fuzzer_log.events.last_mut() {
+                event.file_name = event.file_name.take().or(file_name);
+            }
+        } else if line.starts_with("Done ") {
+            // "Done 10000 runs in 3 second(s)"
+            fuzzer_log.total_runs = value_after(&tokens, "Done");
+            fuzzer_log.total_seconds = value_after(&tokens, "in");
+        } else if let Some((name, value)) = line.split_once(':') {
+            // -print_final_stats
+            match name.trim() {
+                "stat::number_of_executed_units" => {
+                    fuzzer_log.total_runs = value.trim().parse().ok()
+                }
+                "stat::average_exec_per_sec" => fuzzer_log.exec_per_sec = value.trim().parse().ok(),
+                _ => {}
+            }
+        }
+    }
+    fuzzer_log
+}
+
+impl FuzzerLog {
+    fn exec_per_sec(&self) -> Option<f64> {
+        self.exec_per_sec
+            .or_else(|| match (self.total_runs, self.total_seconds) {
+                (Some(runs), Some(seconds)) if seconds > 0.0 => Some(runs as f64 / seconds),
+                _ => None,
+            })
+            .filter(|rate| *rate > 0.0)
+    }
+
+    // milliseconds since the start of the campaign after `runs` executions
+    fn elapsed(&self, runs: u64) -> Option<i64> {
+        self.exec_per_sec()
+            .map(|rate| (runs as f64 / rate * 1000.0) as i64)
+    }
+
+    // the first timestamp, otherwise counted back from the time the log was last written
+    fn start_time(&self, log_modified: i64) -> i64 {
+        self.first_timestamp.unwrap_or_else(|| {
+            let total_runs = self.total_runs.or(self.events.last().map(|e| e.runs));
+            log_modified - total_runs.and_then(|runs| self.elapsed(runs)).unwrap_or(0)
+        })
+    }
+
+    fn event_time(&self, event: &CorpusEvent, start_time: i64) -> Option<i64> {
+        event
+            .timestamp
+            .or_else(|| self.elapsed(event.runs).map(|elapsed| start_time + elapsed))
+    }
+}
+
+/// Metadata of one file of a libFuzzer corpus or seed corpus
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LibFuzzerEntry {
+    pub id: u32,
+    pub is_initial_seed: bool,
+    // milliseconds since the epoch
+    pub executed_on: i64,
+    // milliseconds since the start of the campaign
+    pub execution_time: i64,
+    pub runs: u64,
+    pub coverage: u32,
+}
+
+/// Discovery order, times and coverage of a libFuzzer corpus, reconstructed from the log.
+///
+/// libFuzzer names its corpus files after the SHA1 of their content and records no lineage, so
+/// only the log tells when an input was found. Lines may be prefixed with a unix timestamp;
+/// without one, times are interpolated from the number of runs and the execution speed reported
+/// by `-print_final_stats`. With `-verbosity=2` the log names the file written for each NEW or
+/// REDUCE line, otherwise files are matched to the last line logged before they were modified.
+#[derive(Clone, Debug, Default)]
+pub struct LibFuzzerCorpus {
+    entries: HashMap<String, LibFuzzerEntry>,
+}
+
+fn modified_millis(path: &Path) -> Result<i64, IngestionError> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| {
+            modified
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as i64
+        })
+        .map_err(|e| fuzzer_log_error(path, e))
+}
+
+// (file name, modification time) of all files in `directory`
+fn list_files(directory: &Path) -> Result<Vec<(String, i64)>, IngestionError> {
+    let entries = fs::read_dir(directory).map_err(|e| fuzzer_log_error(directory, e))?;
+    let mut files = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_file() {
+            files.push((
+                entry.file_name().to_string_lossy().into_owned(),
+                modified_millis(&path)?,
+            ));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+impl LibFuzzerCorpus {
+    pub fn read(
+        corpus_directory: &Path,
+        log_path: &Path,
+        seed_corpus_directory: &Path,
+    ) -> Result<LibFuzzerCorpus, IngestionError> {
+        let log = fs::read_to_string(log_path).map_err(|e| fuzzer_log_error(log_path, e))?;
+        let fuzzer_log = parse_log(&log);
+        let start_time = fuzzer_log.start_time(modified_millis(log_path)?);
+
+        let mut entries = HashMap::new();
+        for (file_name, _) in list_files(seed_corpus_directory)? {
+            let id = entries.len() as u32;
+            entries.insert(
+                file_name,
+                LibFuzzerEntry {
+                    id,
+                    is_initial_seed: true,
+                    executed_on: start_time,
+                    execution_time: 0,
+                    runs: 0,
+                    coverage: fuzzer_log.initial_coverage,
+                },
+            );
+        }
+
+        let mut timed_events: Vec<(i64, &CorpusEvent)> = fuzzer_log
+            .events
+            .iter()
+            .filter_map(|event| Some((fuzzer_log.event_time(event, start_time)?, event)))
+            .collect();
+        timed_events.sort_by_key(|(time, _)| *time);
+
+        let mut corpus_entries: Vec<(String, i64, Option<&CorpusEvent>)> = Vec::new();
+        for (file_name, modified) in list_files(corpus_directory)? {
+            if entries.contains_key(&file_name) {
+                continue;
+            }
+            let named_event = fuzzer_log
+                .events
+                .iter()
+                .find(|event| event.file_name.as_ref() == Some(&file_name));
+            let (executed_on, event) = match named_event {
+                Some(event) => (
+                    fuzzer_log.event_time(event, start_time).unwrap_or(modified),
+                    Some(event),
+                ),
+                None => {
+                    let index = timed_events.partition_point(|(time, _)| *time <= modified);
+                    let event = index.checked_sub(1).map(|i| timed_events[i].1);
+                    (modified, event)
+                }
+            };
+            corpus_entries.push((file_name, executed_on, event));
+        }
+        corpus_entries.sort_by(|a, b| (a.1, &a.0).cmp(&(b.1, &b.0)));
+
+        for (file_name, executed_on, event) in corpus_entries {
+            let id = entries.len() as u32;
+            entries.insert(
+                file_name,
+                LibFuzzerEntry {
+                    id,
+                    is_initial_seed: false,
+                    executed_on,
+                    execution_time: executed_on - start_time,
+                    runs: event.map_or(0, |e| e.runs),
+                    coverage: event.map_or(0, |e| e.coverage),
+                },
+            );
+        }
+
+        Ok(LibFuzzerCorpus { entries })
+    }
+
+    /// Returns the entry of the corpus or seed corpus file named `file_name`
+    pub fn get(&self, file_name: &str) -> Option<&LibFuzzerEntry> {
+        self.entries.get(file_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let fuzzer_log = parse_log(
+            "INFO: Seed: 1337\n\
+             1753701940.000 #4\tINITED cov: 12 ft: 13 corp: 3/20b exec/s: 0 rss: 30Mb\n\
+             1753701940.500 #5\tNEW    cov: 14 ft: 15 corp: 4/25b lim: 4 exec/s: 0 rss: 30Mb L: 5/10 MS: 1 ChangeByte-\n\
+             Written 5 bytes to corpus/4a8a08f09d37b73795649038408b5f33\n\
+             1753701942.000 #2048\tREDUCE cov: 14 ft: 15 corp: 4/24b lim: 4 exec/s: 0 rss: 30Mb L: 4/10 MS: 2 EraseBytes-\n\
+             Done 10000 runs in 4 second(s)\n\
+             stat::number_of_executed_units: 10000\n\
+             stat::average_exec_per_sec:     2500\n",
+        );
+        assert_eq!(fuzzer_log.first_timestamp, Some(1753701940000));
+        assert_eq!(fuzzer_log.initial_coverage, 12);
+        assert_eq!(fuzzer_log.events.len(), 2);
+        assert_eq!(
+            fuzzer_log.events[0],
+            CorpusEvent {
+                runs: 5,
+                timestamp: Some(1753701940500),
+                coverage: 14,
+                file_name: Some("4a8a08f09d37b73795649038408b5f33".to_string()),
+            }
+        );
+        assert_eq!(fuzzer_log.events[1].file_name, None);
+        assert_eq!(fuzzer_log.total_runs, Some(10000));
+        assert_eq!(fuzzer_log.exec_per_sec(), Some(2500.0));
+    }
+
+    #[test]
+    fn test_interpolated_times() {
+        let fuzzer_log = parse_log(
+            "#2\tINITED cov: 3 ft: 3 corp: 1/1b exec/s: 0 rss: 30Mb\n\
+             #500\tNEW    cov: 4 ft: 4 corp: 2/3b lim: 4 exec/s: 0 rss: 30Mb L: 2/2 MS: 1 InsertByte-\n\
+             Done 1000 runs in 2 second(s)\n",
+        );
+        let start_time = fuzzer_log.start_time(10000);
+        assert_eq!(start_time, 8000);
+        assert_eq!(
+            fuzzer_log.event_time(&fuzzer_log.events[0], start_time),
+            Some(9000)
+        );
+    }
+
+    #[test]
+    fn test_libfuzzer_corpus() {
+        let test_data_dir = Path::new("../test_data/libfuzzer");
+        let corpus = LibFuzzerCorpus::read(
+            &test_data_dir.join("corpus"),
+            &test_data_dir.join("fuzz.log"),
+            &test_data_dir.join("seeds"),
+        )
+        .unwrap();
+
+        let seed = corpus.get("seed.txt").unwrap();
+        assert!(seed.is_initial_seed);
+        assert_eq!(seed.id, 0);
+        assert_eq!(seed.executed_on, 1753701940000);
+        assert_eq!(seed.coverage, 12);
+
+        let first = corpus
+            .get("3f786850e387550fdab836ed7e6dc881de23001b")
+            .unwrap();
+        assert!(!first.is_initial_seed);
+        assert_eq!(first.id, 1);
+        assert_eq!(first.executed_on, 1753701940500);
+        assert_eq!(first.execution_time, 500);
+        assert_eq!(first.coverage, 14);
+
+        let second = corpus
+            .get("89e6c98d92887913cadf06b2adb97f26cde4849b")
+            .unwrap();
+        assert_eq!(second.id, 2);
+        assert_eq!(second.executed_on, 1753701942000);
+        assert_eq!(second.runs, 2048);
+        assert_eq!(second.coverage, 17);
+        assert!(corpus.get("unknown").is_none());
+
+        let missing_log = LibFuzzerCorpus::read(
+            &test_data_dir.join("corpus"),
+            &test_data_dir.join("missing.log"),
+            &test_data_dir.join("seeds"),
+        );
+        assert!(matches!(missing_log, Err(IngestionError::FuzzerLog { .. })));
+    }
+}
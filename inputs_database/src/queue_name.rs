@@ -0,0 +1,618 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use config::{FuzzerConfig, QueueNameFormat};
+use custom_types::InputMeta;
+use regex::{Captures, Regex};
+
+use crate::{AflRunStats, AmbiguousParent, HonggfuzzCorpus, IngestionError, LibFuzzerCorpus};
+
+/// Reads the metadata a fuzzer encodes in the names of its queue entries.
+///
+/// Trace files are named after the queue entry they were recorded for, with every `,` replaced
+/// by `::` and a `.trace` extension, `file_name` is the path of such a trace file.
+pub trait QueueNameParser: Send + Sync {
+    fn parse(
+        &self,
+        file_name: &str,
+        fuzzer_configuration_id: u32,
+    ) -> Result<InputMeta, IngestionError>;
+
+    /// Inputs whose parents could not be told from the fuzzer's records, for parsers that do not
+    /// take the parents from the names
+    fn get_ambiguous_parents(&self) -> Vec<AmbiguousParent> {
+        Vec::new()
+    }
+}
+
+/// Returns the parser for the queue entries of a fuzzer configuration
+pub fn queue_name_parser_for(
+    fuzzer_config: &FuzzerConfig,
+) -> Result<Box<dyn QueueNameParser>, IngestionError> {
+    Ok(match &fuzzer_config.queue_name_format {
+        QueueNameFormat::PatchedAflPlusPlus => Box::new(PatchedAflNameParser),
+        QueueNameFormat::AflPlusPlus => {
+            // fuzzer_stats and plot_data are next to the queue directory
+            let output_directory = Path::new(&fuzzer_config.inputs_directory_path)
+                .parent()
+                .unwrap_or(Path::new(""));
+            match AflRunStats::read(output_directory) {
+                Ok(run_stats) => Box::new(AflNameParser::new(Some(run_stats))),
+                Err(e) => {
+                    log::warn!("{}, falling back to times relative to the start", e);
+                    Box::new(AflNameParser::new(None))
+                }
+            }
+        }
+        QueueNameFormat::Honggfuzz => Box::new(HonggfuzzNameParser),
+        QueueNameFormat::HonggfuzzOutput {
+            log_path,
+            seed_corpus_path,
+        } => Box::new(HonggfuzzOutputParser::new(HonggfuzzCorpus::read(
+            Path::new(&fuzzer_config.inputs_directory_path),
+            Path::new(log_path),
+            Path::new(seed_corpus_path),
+        )?)),
+        QueueNameFormat::LibFuzzer {
+            log_path,
+            seed_corpus_path,
+        } => Box::new(LibFuzzerNameParser::new(LibFuzzerCorpus::read(
+            Path::new(&fuzzer_config.inputs_directory_path),
+            Path::new(log_path),
+            Path::new(seed_corpus_path),
+        )?)),
+        QueueNameFormat::Regex(pattern) => Box::new(RegexNameParser::new(pattern)?),
+    })
+}
+
+fn invalid_file_name(file_name: &str, reason: String) -> IngestionError {
+    IngestionError::InvalidFileName {
+        file_name: file_name.to_string(),
+        reason,
+    }
+}
+
+fn file_stem_of(file_name: &str) -> Result<String, IngestionError> {
+    match PathBuf::from(file_name)
+        .file_stem()
+        .and_then(|f| f.to_str())
+    {
+        Some(file_stem) => Ok(file_stem.to_string()),
+        None => Err(invalid_file_name(file_name, "no file stem".to_string())),
+    }
+}
+
+fn parse_value<T: FromStr>(file_name: &str, name: &str, value: &str) -> Result<T, IngestionError> {
+    value.parse::<T>().map_err(|_| {
+        invalid_file_name(
+            file_name,
+            format!("unable to parse field {:?} of {:?}", value, name),
+        )
+    })
+}
+
+// "000002+000001" for inputs spliced from two parents
+fn parse_parents(file_name: &str, value: &str) -> Result<Vec<u32>, IngestionError> {
+    value
+        .split('+')
+        .map(|parent| parse_value(file_name, "src", parent.trim()))
+        .collect()
+}
+
+/// The "name:value" fields of a queue entry, in the order they appear in the name.
+///
+/// Names are compared exactly, so a value such as `orig:valid_id.txt` never counts as an `id`.
+/// Fields without a value, like AFL++'s `+cov`, carry no metadata and are left out.
+struct QueueNameFields<'a> {
+    file_name: &'a str,
+    file_stem: String,
+}
+
+impl<'a> QueueNameFields<'a> {
+    fn new(file_name: &'a str) -> Result<Self, IngestionError> {
+        Ok(QueueNameFields {
+            file_name,
+            file_stem: file_stem_of(file_name)?,
+        })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.file_stem
+            .split("::")
+            .filter_map(|field| field.split_once(':'))
+            .find(|(field_name, _)| *field_name == name)
+            .map(|(_, value)| value)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    fn parse<T: FromStr>(&self, name: &str) -> Result<Option<T>, IngestionError> {
+        self.get(name)
+            .map(|value| parse_value(self.file_name, name, value))
+            .transpose()
+    }
+
+    fn require<T: FromStr>(&self, name: &str) -> Result<T, IngestionError> {
+        self.parse(name)?
+            .ok_or_else(|| invalid_file_name(self.file_name, format!("missing field {:?}", name)))
+    }
+
+    fn parents(&self) -> Result<Vec<u32>, IngestionError> {
+        match self.get("src") {
+            Some(value) => parse_parents(self.file_name, value),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn input_meta(&self, fuzzer_configuration_id: u32) -> InputMeta {
+        let mut input_metadata = InputMeta::new();
+        input_metadata.is_initial_seed = self.has("orig");
+        input_metadata.fuzzer_configuration = fuzzer_configuration_id;
+        input_metadata.file_name_stem = self.file_stem.clone();
+        input_metadata
+    }
+}
+
+/// AFL++ with `external/rename_seeds_afl.patch`:
+/// `cycle:2,id:000007,executed_on:1753701941458,src:000002+000001,time:532,execs:1020,op:havoc,…,edges_found:123`
+/// and `id:000001,time:0,executed_on:1753701940885,execs:0,orig:b` for initial seeds.
+pub struct PatchedAflNameParser;
+
+impl QueueNameParser for PatchedAflNameParser {
+    fn parse(
+        &self,
+        file_name: &str,
+        fuzzer_configuration_id: u32,
+    ) -> Result<InputMeta, IngestionError> {
+        let fields = QueueNameFields::new(file_name)?;
+        let mut input_metadata = fields.input_meta(fuzzer_configuration_id);
+        input_metadata.fuzz_input_id = fields.require("id")?;
+        if input_metadata.is_initial_seed {
+            input_metadata.executed_on = fields.parse("executed_on")?.unwrap_or_default();
+            return Ok(input_metadata);
+        }
+
+        input_metadata.executed_on = fields.require("executed_on")?;
+        input_metadata.execution_time = fields.parse("time")?.unwrap_or_default();
+        input_metadata.total_mutations_required_to_generate =
+            fields.parse("execs")?.unwrap_or_default();
+        input_metadata.fuzzer_coverage = fields.parse("edges_found")?.unwrap_or_default();
+        input_metadata.parents = fields.parents()?;
+        Ok(input_metadata)
+    }
+}
+
+/// Stock AFL++: `id:000007,src:000002+000001,time:532,execs:1020,op:havoc,rep:4,+cov` and
+/// `id:000001,time:0,execs:0,orig:b` for initial seeds.
+///
+/// Stock AFL++ records neither the wall clock time nor the edges found in the names, both are
+/// reconstructed from the `AflRunStats` of the instance. Without them, `executed_on` holds the
+/// milliseconds since the start of the campaign and no edges are recorded.
+#[derive(Default)]
+pub struct AflNameParser {
+    run_stats: Option<AflRunStats>,
+}
+
+impl AflNameParser {
+    pub fn new(run_stats: Option<AflRunStats>) -> Self {
+        AflNameParser { run_stats }
+    }
+}
+
+impl QueueNameParser for AflNameParser {
+    fn parse(
+        &self,
+        file_name: &str,
+        fuzzer_configuration_id: u32,
+    ) -> Result<InputMeta, IngestionError> {
+        let fields = QueueNameFields::new(file_name)?;
+        let mut input_metadata = fields.input_meta(fuzzer_configuration_id);
+        input_metadata.fuzz_input_id = fields.require("id")?;
+        input_metadata.execution_time = fields.parse("time")?.unwrap_or_default();
+        input_metadata.executed_on = input_metadata.execution_time;
+        if let Some(run_stats) = &self.run_stats {
+            input_metadata.executed_on = run_stats.executed_on(input_metadata.execution_time);
+            input_metadata.fuzzer_coverage = run_stats.edges_found(input_metadata.execution_time);
+        }
+        input_metadata.total_mutations_required_to_generate =
+            fields.parse("execs")?.unwrap_or_default();
+        if !input_metadata.is_initial_seed {
+            input_metadata.parents = fields.parents()?;
+        }
+        Ok(input_metadata)
+    }
+}
+
+/// Honggfuzz inputs renamed by `external/post_process_honggfuzz_inputs.py`:
+/// `cycle:1,id:12,executed_on:1753701941458,src:3,edges_found:123` and
+/// `id:3,executed_on:1753701940885,edges_found:100,orig:3` for initial seeds.
+pub struct HonggfuzzNameParser;
+
+impl QueueNameParser for HonggfuzzNameParser {
+    fn parse(
+        &self,
+        file_name: &str,
+        fuzzer_configuration_id: u32,
+    ) -> Result<InputMeta, IngestionError> {
+        let fields = QueueNameFields::new(file_name)?;
+        let mut input_metadata = fields.input_meta(fuzzer_configuration_id);
+        input_metadata.fuzz_input_id = fields.require("id")?;
+        input_metadata.executed_on = fields.require("executed_on")?;
+        input_metadata.fuzzer_coverage = fields.parse("edges_found")?.unwrap_or_default();
+        if !input_metadata.is_initial_seed {
+            input_metadata.parents =
+                parse_parents(file_name, fields.require::<String>("src")?.as_str())?;
+        }
+        Ok(input_metadata)
+    }
+}
+
+/// Inputs and initial seeds of a Honggfuzz output directory, named after the file the trace was
+/// recorded for. All metadata comes from the `HonggfuzzCorpus`.
+pub struct HonggfuzzOutputParser {
+    corpus: HonggfuzzCorpus,
+}
+
+impl HonggfuzzOutputParser {
+    pub fn new(corpus: HonggfuzzCorpus) -> Self {
+        HonggfuzzOutputParser { corpus }
+    }
+}
+
+impl QueueNameParser for HonggfuzzOutputParser {
+    fn parse(
+        &self,
+        file_name: &str,
+        fuzzer_configuration_id: u32,
+    ) -> Result<InputMeta, IngestionError> {
+        let file_stem = file_stem_of(file_name)?;
+        let Some(entry) = self.corpus.get(&file_stem.replace("::", ",")) else {
+            return Err(invalid_file_name(
+                file_name,
+                "not in the output directory or initial seeds".to_string(),
+            ));
+        };
+
+        let mut input_metadata = InputMeta::new();
+        input_metadata.is_initial_seed = entry.is_initial_seed;
+        input_metadata.fuzzer_configuration = fuzzer_configuration_id;
+        input_metadata.file_name_stem = file_stem;
+        input_metadata.fuzz_input_id = entry.id;
+        input_metadata.executed_on = entry.executed_on;
+        input_metadata.fuzzer_coverage = entry.edges_found;
+        input_metadata.parents = entry.parents.clone();
+        Ok(input_metadata)
+    }
+
+    fn get_ambiguous_parents(&self) -> Vec<AmbiguousParent> {
+        self.corpus.get_ambiguous_parents().clone()
+    }
+}
+
+/// libFuzzer corpus and seed corpus entries, named after the file the trace was recorded for.
+///
+/// All metadata comes from the `LibFuzzerCorpus`, libFuzzer records no parents.
+pub struct LibFuzzerNameParser {
+    corpus: LibFuzzerCorpus,
+}
+
+impl LibFuzzerNameParser {
+    pub fn new(corpus: LibFuzzerCorpus) -> Self {
+        LibFuzzerNameParser { corpus }
+    }
+}
+
+impl QueueNameParser for LibFuzzerNameParser {
+    fn parse(
+        &self,
+        file_name: &str,
+        fuzzer_configuration_id: u32,
+    ) -> Result<InputMeta, IngestionError> {
+        let file_stem = file_stem_of(file_name)?;
+        let Some(entry) = self.corpus.get(&file_stem.replace("::", ",")) else {
+            return Err(invalid_file_name(
+                file_name,
+                "not in the corpus or seed corpus".to_string(),
+            ));
+        };
+
+        let mut input_metadata = InputMeta::new();
+        input_metadata.is_initial_seed = entry.is_initial_seed;
+        input_metadata.fuzzer_configuration = fuzzer_configuration_id;
+        input_metadata.file_name_stem = file_stem;
+        input_metadata.fuzz_input_id = entry.id;
+        input_metadata.executed_on = entry.executed_on;
+        input_metadata.execution_time = entry.execution_time;
+        input_metadata.total_mutations_required_to_generate =
+            entry.runs.try_into().unwrap_or(u32::MAX);
+        input_metadata.fuzzer_coverage = entry.coverage;
+        Ok(input_metadata)
+    }
+}
+
+/// A user supplied regular expression, see `QueueNameFormat::Regex`.
+///
+/// It is matched against the original queue entry name, i.e. with `,` instead of `::`. Without an
+/// `executed_on` group, `time` is used for the timeline.
+pub struct RegexNameParser {
+    regex: Regex,
+}
+
+impl RegexNameParser {
+    pub fn new(pattern: &str) -> Result<Self, IngestionError> {
+        let regex = Regex::new(pattern).map_err(|e| IngestionError::InvalidNameFormat {
+            pattern: pattern.to_string(),
+            reason: e.to_string(),
+        })?;
+        if !regex.capture_names().any(|name| name == Some("id")) {
+            return Err(IngestionError::InvalidNameFormat {
+                pattern: pattern.to_string(),
+                reason: "missing named group \"id\"".to_string(),
+            });
+        }
+        Ok(RegexNameParser { regex })
+    }
+}
+
+fn capture<T: FromStr>(
+    file_name: &str,
+    captures: &Captures,
+    name: &str,
+) -> Result<Option<T>, IngestionError> {
+    captures
+        .name(name)
+        .map(|value| parse_value(file_name, name, value.as_str()))
+        .transpose()
+}
+
+impl QueueNameParser for RegexNameParser {
+    fn parse(
+        &self,
+        file_name: &str,
+        fuzzer_configuration_id: u32,
+    ) -> Result<InputMeta, IngestionError> {
+        let file_stem = file_stem_of(file_name)?;
+        let queue_name = file_stem.replace("::", ",");
+        let Some(captures) = self.regex.captures(&queue_name) else {
+            return Err(invalid_file_name(
+                file_name,
+                format!("does not match {:?}", self.regex.as_str()),
+            ));
+        };
+
+        let mut input_metadata = InputMeta::new();
+        input_metadata.is_initial_seed = captures.name("orig").is_some();
+        input_metadata.fuzzer_configuration = fuzzer_configuration_id;
+        input_metadata.file_name_stem = file_stem.clone();
+        input_metadata.fuzz_input_id = capture(file_name, &captures, "id")?
+            .ok_or_else(|| invalid_file_name(file_name, "no id".to_string()))?;
+        input_metadata.execution_time = capture(file_name, &captures, "time")?.unwrap_or_default();
+        input_metadata.executed_on =
+            capture(file_name, &captures, "executed_on")?.unwrap_or(input_metadata.execution_time);
+        input_metadata.total_mutations_required_to_generate =
+            capture(file_name, &captures, "execs")?.unwrap_or_default();
+        input_metadata.fuzzer_coverage =
+            capture(file_name, &captures, "edges_found")?.unwrap_or_default();
+        if let Some(src) = captures.name("src") {
+            input_metadata.parents = parse_parents(file_name, src.as_str())?;
+        }
+        Ok(input_metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patched_afl_name() {
+        let input_meta = PatchedAflNameParser.parse("traces/cycle:2::id:000007::executed_on:1753701941458::src:000002+000001::time:532::execs:1020::op:havoc::rep:4::gain:1::score:200::depth:2::bitmap_cvg:0.02::edges_found:123.trace", 3).unwrap();
+        assert!(!input_meta.is_initial_seed);
+        assert_eq!(input_meta.fuzzer_configuration, 3);
+        assert_eq!(input_meta.fuzz_input_id, 7);
+        assert_eq!(input_meta.executed_on, 1753701941458);
+        assert_eq!(input_meta.parents, vec![2, 1]);
+        assert_eq!(input_meta.fuzzer_coverage, 123);
+
+        let seed_meta = PatchedAflNameParser
+            .parse(
+                "traces/id:000001::time:0::executed_on:1753701940885::execs:0::orig:b.trace",
+                3,
+            )
+            .unwrap();
+        assert!(seed_meta.is_initial_seed);
+        assert_eq!(seed_meta.fuzz_input_id, 1);
+        assert_eq!(seed_meta.executed_on, 1753701940885);
+
+        let seed_meta = PatchedAflNameParser
+            .parse(
+                "traces/id:000004::time:0::executed_on:1753701940885::execs:0::orig:valid_id.txt.trace",
+                3,
+            )
+            .unwrap();
+        assert_eq!(seed_meta.fuzz_input_id, 4);
+    }
+
+    #[test]
+    fn test_patched_afl_name_invalid() {
+        assert!(matches!(
+            PatchedAflNameParser
+                .parse("traces/cycle:1::id:abc::executed_on:1753701941117.trace", 0),
+            Err(IngestionError::InvalidFileName { .. })
+        ));
+        assert!(matches!(
+            PatchedAflNameParser.parse("traces/cycle:1::id:000002.trace", 0),
+            Err(IngestionError::InvalidFileName { .. })
+        ));
+    }
+
+    #[test]
+    fn test_afl_name() {
+        let input_meta = AflNameParser::default()
+            .parse(
+                "traces/id:000012::src:000003+000010::time:7520::execs:41230::op:splice::rep:8::+cov.trace",
+                1,
+            )
+            .unwrap();
+        assert!(!input_meta.is_initial_seed);
+        assert_eq!(input_meta.fuzz_input_id, 12);
+        assert_eq!(input_meta.parents, vec![3, 10]);
+        assert_eq!(input_meta.execution_time, 7520);
+        assert_eq!(input_meta.executed_on, 7520);
+        assert_eq!(input_meta.total_mutations_required_to_generate, 41230);
+
+        let seed_meta = AflNameParser::default()
+            .parse("traces/id:000000::time:0::execs:0::orig:seed.trace", 1)
+            .unwrap();
+        assert!(seed_meta.is_initial_seed);
+        assert_eq!(seed_meta.fuzz_input_id, 0);
+    }
+
+    #[test]
+    fn test_afl_name_with_run_stats() {
+        let fuzzer_config = FuzzerConfig {
+            fuzzer_configuration: "afl++".to_string(),
+            traces_directory_path: "../test_data/afl_stock/traces".to_string(),
+            inputs_directory_path: "../test_data/afl_stock/queue".to_string(),
+            fuzzer_configuration_id: 1,
+            queue_name_format: QueueNameFormat::AflPlusPlus,
+        };
+        let parser = queue_name_parser_for(&fuzzer_config).unwrap();
+
+        let input_meta = parser
+            .parse(
+                "traces/id:000012::src:000003+000010::time:7520::execs:41230::op:splice::rep:8::+cov.trace",
+                1,
+            )
+            .unwrap();
+        assert_eq!(input_meta.execution_time, 7520);
+        assert_eq!(input_meta.executed_on, 1753701947520);
+        assert_eq!(input_meta.fuzzer_coverage, 123);
+
+        let seed_meta = parser
+            .parse("traces/id:000000::time:0::execs:0::orig:seed.trace", 1)
+            .unwrap();
+        assert_eq!(seed_meta.executed_on, 1753701940000);
+    }
+
+    #[test]
+    fn test_libfuzzer_name() {
+        let fuzzer_config = FuzzerConfig {
+            fuzzer_configuration: "libfuzzer".to_string(),
+            traces_directory_path: "../test_data/libfuzzer/traces".to_string(),
+            inputs_directory_path: "../test_data/libfuzzer/corpus".to_string(),
+            fuzzer_configuration_id: 5,
+            queue_name_format: QueueNameFormat::LibFuzzer {
+                log_path: "../test_data/libfuzzer/fuzz.log".to_string(),
+                seed_corpus_path: "../test_data/libfuzzer/seeds".to_string(),
+            },
+        };
+        let parser = queue_name_parser_for(&fuzzer_config).unwrap();
+
+        let input_meta = parser
+            .parse("traces/89e6c98d92887913cadf06b2adb97f26cde4849b.trace", 5)
+            .unwrap();
+        assert!(!input_meta.is_initial_seed);
+        assert_eq!(input_meta.fuzz_input_id, 2);
+        assert_eq!(input_meta.executed_on, 1753701942000);
+        assert_eq!(input_meta.fuzzer_coverage, 17);
+        assert!(input_meta.parents.is_empty());
+
+        let seed_meta = parser.parse("traces/seed.txt.trace", 5).unwrap();
+        assert!(seed_meta.is_initial_seed);
+        assert_eq!(seed_meta.fuzz_input_id, 0);
+        assert!(parser.parse("traces/unknown.trace", 5).is_err());
+    }
+
+    #[test]
+    fn test_honggfuzz_output_name() {
+        let fuzzer_config = FuzzerConfig {
+            fuzzer_configuration: "hfuzz".to_string(),
+            traces_directory_path: "../test_data/honggfuzz/traces".to_string(),
+            inputs_directory_path: "../test_data/honggfuzz/hfuzz-out".to_string(),
+            fuzzer_configuration_id: 2,
+            queue_name_format: QueueNameFormat::HonggfuzzOutput {
+                log_path: "../test_data/honggfuzz/hfuzz.log".to_string(),
+                seed_corpus_path: "../test_data/honggfuzz/seeds".to_string(),
+            },
+        };
+        let parser = queue_name_parser_for(&fuzzer_config).unwrap();
+
+        let input_meta = parser
+            .parse(
+                "traces/id:5::executed_on:1753701944000::edges_found:14::0000000000000005eeeeeeeeeeeeeeee.00000002.honggfuzz.cov.trace",
+                2,
+            )
+            .unwrap();
+        assert!(!input_meta.is_initial_seed);
+        assert_eq!(input_meta.fuzz_input_id, 5);
+        assert_eq!(input_meta.executed_on, 1753701944000);
+        assert_eq!(input_meta.fuzzer_coverage, 14);
+        assert_eq!(input_meta.parents, vec![2]);
+
+        let seed_meta = parser.parse("traces/b.txt.trace", 2).unwrap();
+        assert!(seed_meta.is_initial_seed);
+        assert_eq!(seed_meta.fuzz_input_id, 10);
+        assert_eq!(seed_meta.executed_on, 1753701941500);
+        assert_eq!(parser.get_ambiguous_parents().len(), 2);
+    }
+
+    #[test]
+    fn test_honggfuzz_name() {
+        let input_meta = HonggfuzzNameParser
+            .parse(
+                "traces/cycle:1::id:12::executed_on:1753701941458::src:3::edges_found:123.trace",
+                2,
+            )
+            .unwrap();
+        assert_eq!(input_meta.fuzz_input_id, 12);
+        assert_eq!(input_meta.parents, vec![3]);
+        assert_eq!(input_meta.fuzzer_coverage, 123);
+
+        let seed_meta = HonggfuzzNameParser
+            .parse(
+                "traces/id:3::executed_on:1753701940885::edges_found:100::orig:3.trace",
+                2,
+            )
+            .unwrap();
+        assert!(seed_meta.is_initial_seed);
+        assert_eq!(seed_meta.executed_on, 1753701940885);
+
+        assert!(HonggfuzzNameParser
+            .parse(
+                "traces/cycle:1::id:12::executed_on:1753701941458::edges_found:123.trace",
+                2
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_regex_name() {
+        let parser = RegexNameParser::new(
+            r"^(?:seed-(?P<orig>\w+)-)?(?P<id>\d+)(?:-from-(?P<src>[\d+]+))?-at-(?P<time>\d+)$",
+        )
+        .unwrap();
+        let input_meta = parser.parse("traces/17-from-2+5-at-1200.trace", 4).unwrap();
+        assert!(!input_meta.is_initial_seed);
+        assert_eq!(input_meta.fuzz_input_id, 17);
+        assert_eq!(input_meta.parents, vec![2, 5]);
+        assert_eq!(input_meta.executed_on, 1200);
+
+        let seed_meta = parser.parse("traces/seed-a-1-at-0.trace", 4).unwrap();
+        assert!(seed_meta.is_initial_seed);
+        assert!(parser.parse("traces/unrelated.trace", 4).is_err());
+
+        assert!(matches!(
+            RegexNameParser::new(r"(?P<src>\d+)"),
+            Err(IngestionError::InvalidNameFormat { .. })
+        ));
+        assert!(matches!(
+            RegexNameParser::new(r"(?P<id>\d+"),
+            Err(IngestionError::InvalidNameFormat { .. })
+        ));
+    }
+}
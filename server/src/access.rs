@@ -0,0 +1,365 @@
+//! Who may use the server: the CORS policy for the web client and the optional authentication in
+//! front of every route
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use actix_cors::Cors;
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header,
+    middleware::Next,
+    web, Error, HttpResponse,
+};
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use config::AuthConfig;
+use rustls::crypto::ring;
+use serde::Deserialize;
+
+/// The `Authorization` header every request has to carry, as app data of the server
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequiredAuthorization {
+    scheme: &'static str,
+    credentials: String,
+}
+
+impl RequiredAuthorization {
+    pub fn new(auth: &AuthConfig) -> Self {
+        match auth {
+            AuthConfig::BearerToken(token) => RequiredAuthorization {
+                scheme: "Bearer",
+                credentials: token.clone(),
+            },
+            AuthConfig::Basic { username, password } => RequiredAuthorization {
+                scheme: "Basic",
+                credentials: STANDARD.encode(format!("{}:{}", username, password)),
+            },
+        }
+    }
+
+    /// Whether `value` of an `Authorization` header matches. The scheme is case insensitive, the
+    /// credentials are compared in constant time so that their length is all a client can learn.
+    fn accepts(&self, value: &str) -> bool {
+        let Some((scheme, credentials)) = value.trim().split_once(' ') else {
+            return false;
+        };
+        let (credentials, expected) = (credentials.trim().as_bytes(), self.credentials.as_bytes());
+        scheme.eq_ignore_ascii_case(self.scheme)
+            && credentials.len() == expected.len()
+            && credentials
+                .iter()
+                .zip(expected)
+                .fold(0, |difference, (a, b)| difference | (a ^ b))
+                == 0
+    }
+}
+
+/// Short-lived tokens that open `/events` as `/events?token=…`, for browsers whose `EventSource`
+/// cannot send an `Authorization` header. They are handed out by `/events_token` to clients that
+/// have the `RequiredAuthorization`.
+pub struct EventsTokens {
+    lifetime: Duration,
+    expiries: Mutex<HashMap<String, Instant>>,
+}
+
+impl EventsTokens {
+    pub fn new(lifetime: Duration) -> Self {
+        EventsTokens {
+            lifetime,
+            expiries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get_lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    /// A new random token, `None` if the system has no randomness to give
+    pub fn issue(&self) -> Option<String> {
+        let mut bytes = [0u8; 32];
+        ring::default_provider()
+            .secure_random
+            .fill(&mut bytes)
+            .ok()?;
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+
+        let now = Instant::now();
+        let mut expiries = self.expiries.lock().unwrap();
+        expiries.retain(|_, expiry| *expiry > now);
+        expiries.insert(token.clone(), now + self.lifetime);
+        Some(token)
+    }
+
+    /// Whether `token` was issued and has not expired yet. It stays valid until then, so that
+    /// `EventSource` can reconnect with it.
+    fn accepts(&self, token: &str) -> bool {
+        let expiries = self.expiries.lock().unwrap();
+        expiries
+            .get(token)
+            .is_some_and(|expiry| *expiry > Instant::now())
+    }
+}
+
+#[derive(Deserialize)]
+struct EventsTokenQuery {
+    token: String,
+}
+
+/// Whether `request` opens `/events` with a token of the `EventsTokens` of the server
+fn has_events_token(request: &ServiceRequest) -> bool {
+    if request.path() != "/events" {
+        return false;
+    }
+    let Some(tokens) = request.app_data::<web::Data<EventsTokens>>() else {
+        return false;
+    };
+    web::Query::<EventsTokenQuery>::from_query(request.query_string())
+        .is_ok_and(|query| tokens.accepts(&query.token))
+}
+
+/// CORS policy allowing the web client served from `allowed_origins` to call the server
+pub fn cors(allowed_origins: &[String]) -> Cors {
+    let cors = Cors::default()
+        .allowed_methods(vec!["GET", "POST"])
+        .allowed_headers(vec![
+            header::ACCEPT,
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+        ])
+        // tells the web client whether to ask for a token or a username and password
+        .expose_headers(vec![header::WWW_AUTHENTICATE]);
+    if allowed_origins.iter().any(|origin| origin == "*") {
+        return cors.allow_any_origin();
+    }
+    allowed_origins
+        .iter()
+        .fold(cors, |cors, origin| cors.allowed_origin(origin))
+}
+
+/// Rejects requests without the `RequiredAuthorization` of the server, if it has one, except for
+/// `/events` opened with one of its `EventsTokens`
+pub async fn authorize(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    if let Some(required) = request.app_data::<web::Data<RequiredAuthorization>>() {
+        let is_authorized = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| required.accepts(value))
+            || has_events_token(&request);
+        if !is_authorized {
+            let challenge = match required.scheme {
+                "Basic" => "Basic realm=\"SeedUI\"",
+                scheme => scheme,
+            };
+            log::warn!("unauthorized {} {}", request.method(), request.path());
+            let response = HttpResponse::Unauthorized()
+                .insert_header((header::WWW_AUTHENTICATE, challenge))
+                .finish();
+            return Ok(request.into_response(response).map_into_right_body());
+        }
+    }
+
+    next.call(request)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::AppState;
+    use actix_web::{middleware::from_fn, test as actix_test, App};
+    use inputs_database::InputsDatabase;
+    use sut_database::SUT;
+
+    #[test]
+    fn test_accepts() {
+        let bearer = RequiredAuthorization::new(&AuthConfig::BearerToken("secret".to_string()));
+        assert!(bearer.accepts("Bearer secret"));
+        assert!(bearer.accepts("bearer  secret "));
+        assert!(!bearer.accepts("Bearer secre"));
+        assert!(!bearer.accepts("Bearer secret2"));
+        assert!(!bearer.accepts("Basic secret"));
+        assert!(!bearer.accepts("secret"));
+
+        let basic = RequiredAuthorization::new(&AuthConfig::Basic {
+            username: "user".to_string(),
+            password: "pass".to_string(),
+        });
+        assert!(basic.accepts("Basic dXNlcjpwYXNz"));
+        assert!(!basic.accepts("Basic dXNlcjpwYXNzMg=="));
+    }
+
+    #[actix_web::test]
+    async fn test_unauthenticated_requests_rejected() {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(from_fn(authorize))
+                .wrap(cors(&["http://localhost:3000".to_string()]))
+                .app_data(web::Data::new(AppState::new(
+                    InputsDatabase::new(),
+                    SUT::new(),
+                )))
+                .app_data(web::Data::new(RequiredAuthorization::new(
+                    &AuthConfig::BearerToken("secret".to_string()),
+                )))
+                .configure(crate::configure_routes),
+        )
+        .await;
+
+        let response = actix_test::call_service(
+            &app,
+            actix_test::TestRequest::get()
+                .uri("/functions")
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), 401);
+        assert_eq!(
+            response.headers().get(header::WWW_AUTHENTICATE).unwrap(),
+            "Bearer"
+        );
+
+        // the web client reads the challenge to know what to ask for
+        let request = actix_test::TestRequest::get()
+            .uri("/functions")
+            .insert_header((header::ORIGIN, "http://localhost:3000"))
+            .to_request();
+        let response = actix_test::call_service(&app, request).await;
+        assert_eq!(response.status(), 401);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::ACCESS_CONTROL_EXPOSE_HEADERS)
+                .unwrap(),
+            "www-authenticate"
+        );
+
+        let request = actix_test::TestRequest::get()
+            .uri("/functions")
+            .insert_header((header::AUTHORIZATION, "Bearer wrong"))
+            .to_request();
+        assert_eq!(actix_test::call_service(&app, request).await.status(), 401);
+
+        let request = actix_test::TestRequest::get()
+            .uri("/functions")
+            .insert_header((header::AUTHORIZATION, "Bearer secret"))
+            .to_request();
+        let response = actix_test::call_service(&app, request).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(actix_test::read_body(response).await, "[]");
+
+        // browsers send preflight requests without credentials
+        let request = actix_test::TestRequest::default()
+            .method(actix_web::http::Method::OPTIONS)
+            .uri("/functions")
+            .insert_header((header::ORIGIN, "http://localhost:3000"))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, "GET"))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization"))
+            .to_request();
+        assert_eq!(actix_test::call_service(&app, request).await.status(), 200);
+    }
+
+    #[actix_web::test]
+    async fn test_events_token() {
+        let tokens = web::Data::new(EventsTokens::new(Duration::from_secs(60)));
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(from_fn(authorize))
+                .app_data(web::Data::new(AppState::new(
+                    InputsDatabase::new(),
+                    SUT::new(),
+                )))
+                .app_data(tokens.clone())
+                .app_data(web::Data::new(RequiredAuthorization::new(
+                    &AuthConfig::BearerToken("secret".to_string()),
+                )))
+                .configure(crate::configure_routes),
+        )
+        .await;
+
+        let request = actix_test::TestRequest::post()
+            .uri("/events_token")
+            .to_request();
+        assert_eq!(actix_test::call_service(&app, request).await.status(), 401);
+        let request = actix_test::TestRequest::get()
+            .uri("/events?token=guessed")
+            .to_request();
+        assert_eq!(actix_test::call_service(&app, request).await.status(), 401);
+
+        let request = actix_test::TestRequest::post()
+            .uri("/events_token")
+            .insert_header((header::AUTHORIZATION, "Bearer secret"))
+            .to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, request).await;
+        assert_eq!(response["expires_in_ms"], 60000);
+        let token = response["token"].as_str().unwrap();
+
+        let request = actix_test::TestRequest::get()
+            .uri(&format!("/events?token={}", token))
+            .to_request();
+        let response = actix_test::call_service(&app, request).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+
+        // the token opens nothing but /events
+        let request = actix_test::TestRequest::get()
+            .uri(&format!("/functions?token={}", token))
+            .to_request();
+        assert_eq!(actix_test::call_service(&app, request).await.status(), 401);
+
+        // and only until it expires
+        let expired = EventsTokens::new(Duration::ZERO);
+        let token = expired.issue().unwrap();
+        assert!(!expired.accepts(&token));
+        assert!(tokens.accepts(&tokens.issue().unwrap()));
+    }
+
+    #[actix_web::test]
+    async fn test_cors_allow_list() {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(from_fn(authorize))
+                .wrap(cors(&["http://localhost:3000".to_string()]))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let request = actix_test::TestRequest::get()
+            .uri("/")
+            .insert_header((header::ORIGIN, "http://localhost:3000"))
+            .to_request();
+        let response = actix_test::call_service(&app, request).await;
+        assert_eq!(
+            response
+                .headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            "http://localhost:3000"
+        );
+
+        // without a RequiredAuthorization, only the origin is checked and browsers block responses
+        // that do not allow it
+        let request = actix_test::TestRequest::get()
+            .uri("/")
+            .insert_header((header::ORIGIN, "https://evil.example.com"))
+            .to_request();
+        let response = actix_test::call_service(&app, request).await;
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+}
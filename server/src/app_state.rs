@@ -1,12 +1,22 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    process,
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
 use inputs_database::InputsDatabase;
 use sut_database::SUT;
 
-pub struct AppState {
+use crate::events::Broadcaster;
+
+/// Both databases, they are only ever changed together
+pub struct Databases {
     inputs_db: InputsDatabase,
     sut_db: SUT,
+    generation: u64,
 }
 
-impl AppState {
+impl Databases {
     pub fn get_inputs_db(&self) -> &InputsDatabase {
         &self.inputs_db
     }
@@ -15,10 +25,67 @@ impl AppState {
         &self.sut_db
     }
 
+    /// Number of times new inputs were ingested since the server started
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Ingests new inputs through `ingest` and counts the new generation. The server exits if
+    /// `ingest` panics: the hit counts of the SUT may already include an input that was not
+    /// recorded, and those databases must not be served.
+    pub fn update<T>(&mut self, ingest: impl FnOnce(&mut InputsDatabase, &mut SUT) -> T) -> T {
+        let ingested = panic::catch_unwind(AssertUnwindSafe(|| {
+            ingest(&mut self.inputs_db, &mut self.sut_db)
+        }));
+        let Ok(result) = ingested else {
+            log::error!("ingesting new traces panicked, the databases are inconsistent");
+            process::exit(1);
+        };
+        self.generation += 1;
+        result
+    }
+}
+
+pub struct AppState {
+    databases: RwLock<Databases>,
+    watch_interval_ms: Option<u64>,
+    events: Broadcaster,
+}
+
+impl AppState {
+    /// The databases for the duration of a request, the traces directories are not checked for
+    /// new traces while any request holds them
+    pub fn read(&self) -> RwLockReadGuard<'_, Databases> {
+        // Databases::update does not return from a panic, the databases are never half updated
+        self.databases.read().unwrap()
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, Databases> {
+        self.databases.write().unwrap()
+    }
+
+    pub fn get_watch_interval_ms(&self) -> Option<u64> {
+        self.watch_interval_ms
+    }
+
+    pub fn get_events(&self) -> &Broadcaster {
+        &self.events
+    }
+
     pub fn new(inputs: InputsDatabase, sut: SUT) -> Self {
         AppState {
-            inputs_db: inputs.clone(),
-            sut_db: sut.clone(),
+            databases: RwLock::new(Databases {
+                inputs_db: inputs,
+                sut_db: sut,
+                generation: 0,
+            }),
+            watch_interval_ms: None,
+            events: Broadcaster::default(),
         }
     }
+
+    pub fn watching_every(mut self, watch_interval_ms: Option<u64>) -> Self {
+        self.watch_interval_ms = watch_interval_ms;
+        self
+    }
 }
@@ -0,0 +1,284 @@
+use std::{
+    net::ToSocketAddrs,
+    path::{Path, PathBuf},
+};
+
+use config::{AuthConfig, CoverageBackend, LogLevel, ServerConfig, UserConfig};
+
+pub const USAGE: &str = "Usage: server [options] path/to/config.ron
+       server extract path/to/config.ron
+
+Options, overriding the server section of the configuration:
+  --bind <address>     address to listen on (default 127.0.0.1)
+  --port <port>        port to listen on (default 8080)
+  --workers <count>    number of threads handling requests (default 1)
+  --log-level <level>  off, error, warn, info, debug or trace (default info)
+  --snapshot <path>    snapshot of the databases (default path/to/config.ron.snapshot)
+  --watch <ms>         check the traces directories for new traces every <ms> milliseconds
+  --check-config       check the configuration and its paths, then exit
+  --help               print this help";
+
+/// What the server was asked to do on the command line
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Args {
+    pub extract: bool,
+    pub check_config: bool,
+    pub help: bool,
+    pub config_path: PathBuf,
+    pub bind_address: Option<String>,
+    pub port: Option<u16>,
+    pub workers: Option<usize>,
+    pub log_level: Option<LogLevel>,
+    pub snapshot_path: Option<String>,
+    pub watch_interval_ms: Option<u64>,
+}
+
+impl Args {
+    /// Parses the arguments following the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut config_path = None;
+        let mut args = args.into_iter().peekable();
+        if args.peek().is_some_and(|arg| arg == "extract") {
+            parsed.extract = true;
+            args.next();
+        }
+
+        while let Some(arg) = args.next() {
+            // both --port 8080 and --port=8080
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+                _ => (arg.clone(), None),
+            };
+            let mut value = |name: &str| match inline_value {
+                Some(value) => Ok(value.to_string()),
+                None => args.next().ok_or_else(|| format!("{} needs a value", name)),
+            };
+            match flag.as_str() {
+                "--bind" => parsed.bind_address = Some(value("--bind")?),
+                "--port" => {
+                    let port = value("--port")?;
+                    parsed.port = Some(port.parse().map_err(|_| format!("invalid port {}", port))?);
+                }
+                "--workers" => {
+                    let workers = value("--workers")?;
+                    parsed.workers = match workers.parse() {
+                        Ok(0) | Err(_) => return Err(format!("invalid worker count {}", workers)),
+                        Ok(workers) => Some(workers),
+                    };
+                }
+                "--log-level" => parsed.log_level = Some(value("--log-level")?.parse()?),
+                "--snapshot" => parsed.snapshot_path = Some(value("--snapshot")?),
+                "--watch" => {
+                    let interval = value("--watch")?;
+                    parsed.watch_interval_ms = match interval.parse() {
+                        Ok(0) | Err(_) => {
+                            return Err(format!("invalid watch interval {}", interval))
+                        }
+                        Ok(interval) => Some(interval),
+                    };
+                }
+                "--check-config" => parsed.check_config = true,
+                "--help" | "-h" => parsed.help = true,
+                _ if flag.starts_with('-') => return Err(format!("unknown option {}", arg)),
+                _ if config_path.is_none() => config_path = Some(PathBuf::from(arg)),
+                _ => return Err(format!("unexpected argument {}", arg)),
+            }
+        }
+
+        match config_path {
+            Some(config_path) => parsed.config_path = config_path,
+            None if parsed.help => {}
+            None => return Err("missing configuration file".to_string()),
+        }
+        Ok(parsed)
+    }
+
+    /// Overrides the settings of `server_config` given on the command line
+    pub fn apply_to(&self, server_config: &mut ServerConfig) {
+        if let Some(bind_address) = &self.bind_address {
+            server_config.bind_address = bind_address.clone();
+        }
+        if let Some(port) = self.port {
+            server_config.port = port;
+        }
+        if let Some(workers) = self.workers {
+            server_config.workers = workers;
+        }
+        if let Some(log_level) = self.log_level {
+            server_config.log_level = log_level;
+        }
+        if let Some(snapshot_path) = &self.snapshot_path {
+            server_config.snapshot_path = Some(snapshot_path.clone());
+        }
+        if let Some(watch_interval_ms) = self.watch_interval_ms {
+            server_config.watch_interval_ms = Some(watch_interval_ms);
+        }
+    }
+}
+
+/// Problems of a configuration that parses but would not give a working server: UserConfig::parse
+/// already checked that every path exists.
+pub fn config_problems(config: &UserConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    // source based backends do not read the target binary
+    if matches!(
+        config.target_info.coverage_backend,
+        CoverageBackend::DrCov | CoverageBackend::DrCovFullTrace | CoverageBackend::Sancov
+    ) {
+        for module_path in config.target_info.module_paths() {
+            if !Path::new(module_path).is_file() {
+                problems.push(format!("module {} is not a file", module_path));
+            }
+        }
+    }
+    for fuzzer_info in config.fuzzer_infos.iter() {
+        if !Path::new(&fuzzer_info.traces_directory_path).is_dir() {
+            problems.push(format!(
+                "traces of {} in {} are not a directory",
+                fuzzer_info.fuzzer_configuration, fuzzer_info.traces_directory_path
+            ));
+        }
+    }
+    let server = &config.server;
+    if (server.bind_address.as_str(), server.port)
+        .to_socket_addrs()
+        .is_err()
+    {
+        problems.push(format!("invalid bind address {}", server.bind_address));
+    }
+    if server.workers == 0 {
+        problems.push("no workers".to_string());
+    }
+    if server.watch_interval_ms == Some(0) {
+        problems.push("watch interval of 0 ms".to_string());
+    }
+    match &server.auth {
+        Some(AuthConfig::BearerToken(token)) if token.is_empty() => {
+            problems.push("empty bearer token".to_string())
+        }
+        Some(AuthConfig::Basic { username, password })
+            if username.is_empty() || password.is_empty() =>
+        {
+            problems.push("empty username or password".to_string())
+        }
+        _ => {}
+    }
+    if let Some(tls) = &server.tls {
+        if let Err(e) = crate::tls::server_config(tls) {
+            problems.push(e.to_string());
+        }
+    }
+    if server.allowed_origins.is_empty() {
+        problems.push("no allowed origins, the web client cannot call the server".to_string());
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::lcov_target_user_config;
+    use config::TlsConfig;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse(&["config.ron"]).unwrap(),
+            Args {
+                config_path: PathBuf::from("config.ron"),
+                ..Args::default()
+            }
+        );
+        assert_eq!(
+            parse(&[
+                "--bind",
+                "0.0.0.0",
+                "--port=8081",
+                "config.ron",
+                "--workers",
+                "4",
+                "--log-level",
+                "debug",
+                "--snapshot",
+                "/tmp/readelf.snapshot",
+                "--watch",
+                "5000",
+                "--check-config",
+            ])
+            .unwrap(),
+            Args {
+                check_config: true,
+                config_path: PathBuf::from("config.ron"),
+                bind_address: Some("0.0.0.0".to_string()),
+                port: Some(8081),
+                workers: Some(4),
+                log_level: Some(LogLevel::Debug),
+                snapshot_path: Some("/tmp/readelf.snapshot".to_string()),
+                watch_interval_ms: Some(5000),
+                ..Args::default()
+            }
+        );
+        let extract = parse(&["extract", "config.ron"]).unwrap();
+        assert!(extract.extract);
+        assert_eq!(extract.config_path, PathBuf::from("config.ron"));
+        assert!(parse(&["--help"]).unwrap().help);
+    }
+
+    #[test]
+    fn test_parse_args_invalid() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["config.ron", "--port"]).is_err());
+        assert!(parse(&["config.ron", "--port", "80808"]).is_err());
+        assert!(parse(&["config.ron", "--workers", "0"]).is_err());
+        assert!(parse(&["config.ron", "--watch", "0"]).is_err());
+        assert!(parse(&["config.ron", "--log-level", "verbose"]).is_err());
+        assert!(parse(&["config.ron", "--tls"]).is_err());
+        assert!(parse(&["config.ron", "other.ron"]).is_err());
+    }
+
+    #[test]
+    fn test_apply_args() {
+        let mut server_config = ServerConfig {
+            port: 9000,
+            log_level: LogLevel::Warn,
+            ..ServerConfig::default()
+        };
+        parse(&["config.ron", "--port", "8081"])
+            .unwrap()
+            .apply_to(&mut server_config);
+        assert_eq!(
+            server_config,
+            ServerConfig {
+                port: 8081,
+                log_level: LogLevel::Warn,
+                ..ServerConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_config_problems() {
+        let test_data_dir = std::fs::canonicalize("../test_data/lcov_target").unwrap();
+        let mut config = lcov_target_user_config(&test_data_dir.join("traces"));
+        assert!(config_problems(&config).is_empty());
+
+        // drcov traces are mapped through the target binary
+        config.target_info.coverage_backend = CoverageBackend::DrCov;
+        config.fuzzer_infos[0].traces_directory_path = test_data_dir
+            .join("sources/test.c")
+            .to_string_lossy()
+            .into_owned();
+        config.server.bind_address = "not an address".to_string();
+        config.server.auth = Some(AuthConfig::BearerToken(String::new()));
+        config.server.tls = Some(TlsConfig {
+            certificate_path: "../test_data/tls/key.pem".to_string(),
+            private_key_path: "../test_data/tls/key.pem".to_string(),
+        });
+        assert_eq!(config_problems(&config).len(), 5);
+    }
+}
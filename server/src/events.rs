@@ -0,0 +1,408 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+use actix_web::{web::Bytes, Error};
+use custom_types::{FileId, InputId, LineId};
+use futures_core::Stream;
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::{app_state::Databases, responders::UIReloadStatus};
+
+// events a client has not received yet, a client falling further behind is disconnected and
+// expected to reconnect
+const CLIENT_BUFFER: usize = 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UICoveredLine {
+    pub file_id: FileId,
+    pub file_name: String,
+    pub line: u32,
+}
+
+/// An input ingested while the server runs
+#[derive(Clone, Debug, Serialize)]
+pub struct UINewInput {
+    pub fuzzer_configuration_id: u32,
+    pub input_id: u32,
+    pub file_name_stem: String,
+    pub is_initial_seed: bool,
+    pub executed_on: i64,
+    /// The initial seeds the input descends from, the input itself for an initial seed
+    pub initial_seed_ids: Vec<u32>,
+    /// Lines no input of the fuzzer configuration covered before
+    pub new_lines: Vec<UICoveredLine>,
+}
+
+/// Lines an initial seed and its descendants cover since the last ingestion
+#[derive(Clone, Debug, Serialize)]
+pub struct UIInitialSeedCoverage {
+    pub fuzzer_configuration_id: u32,
+    pub initial_seed_id: u32,
+    pub new_lines: Vec<UICoveredLine>,
+    pub lines_covered: usize,
+}
+
+#[derive(Clone, Debug)]
+pub enum Event {
+    Reload(UIReloadStatus),
+    NewInput(UINewInput),
+    InitialSeedCoverage(UIInitialSeedCoverage),
+}
+
+impl Event {
+    /// The event as sent to `/events` clients, named after its kind
+    pub fn to_sse(&self) -> Bytes {
+        let (name, data) = match self {
+            Event::Reload(status) => ("reload", serde_json::to_string(status)),
+            Event::NewInput(new_input) => ("new_input", serde_json::to_string(new_input)),
+            Event::InitialSeedCoverage(coverage) => {
+                ("initial_seed_coverage", serde_json::to_string(coverage))
+            }
+        };
+        Bytes::from(format!("event: {}\ndata: {}\n\n", name, data.unwrap()))
+    }
+}
+
+/// The coverage before each ingestion, to tell which lines the new inputs cover first
+pub struct CoverageTracker {
+    covered_lines: HashMap<u32, HashSet<LineId>>,
+    // filled for the initial seeds whose descendants were ingested while running
+    initial_seed_coverage: HashMap<InputId, HashSet<LineId>>,
+}
+
+impl CoverageTracker {
+    pub fn new(databases: &Databases) -> Self {
+        let mut covered_lines: HashMap<u32, HashSet<LineId>> = HashMap::new();
+        for (line_id, line_meta) in databases.get_sut_db().get_file_id_line_num_line_meta_map() {
+            for fuzzer_configuration_id in line_meta.fuzzer_configuration_ids.iter() {
+                covered_lines
+                    .entry(*fuzzer_configuration_id)
+                    .or_default()
+                    .insert(*line_id);
+            }
+        }
+        CoverageTracker {
+            covered_lines,
+            initial_seed_coverage: HashMap::new(),
+        }
+    }
+
+    /// Lines covered by an initial seed and all its descendants added before `first_new_input_id`
+    fn coverage_before(
+        databases: &Databases,
+        initial_seed_id: &InputId,
+        first_new_input_id: usize,
+    ) -> HashSet<LineId> {
+        let input_db = databases.get_inputs_db();
+        let Some(initial_seed_meta) = input_db.find_input_meta_for(initial_seed_id) else {
+            return HashSet::new();
+        };
+        let mut input_ids = vec![*initial_seed_id];
+        let fuzzer_configuration_id = initial_seed_meta.fuzzer_configuration;
+        let fuzz_input_id = initial_seed_meta.fuzz_input_id;
+        if input_db.has_children_for(&fuzzer_configuration_id, &fuzz_input_id) {
+            input_ids.extend(
+                input_db
+                    .get_all_children_input_ids_for(&fuzzer_configuration_id, &vec![fuzz_input_id]),
+            );
+        }
+        input_ids
+            .iter()
+            .filter(|input_id| input_id.as_usize() < first_new_input_id)
+            .filter_map(|input_id| input_db.find_input_meta_for(input_id))
+            .flat_map(|input_meta| input_meta.source_line_coverage.iter().copied())
+            .collect()
+    }
+
+    fn ui_lines(
+        databases: &Databases,
+        lines: impl IntoIterator<Item = LineId>,
+    ) -> Vec<UICoveredLine> {
+        let mut ui_lines: Vec<UICoveredLine> = lines
+            .into_iter()
+            .map(|line_id| UICoveredLine {
+                file_id: line_id.file(),
+                file_name: databases
+                    .get_sut_db()
+                    .get_file_meta(&line_id.file())
+                    .map(|file_meta| file_meta.name.clone())
+                    .unwrap_or_default(),
+                line: line_id.num(),
+            })
+            .collect();
+        ui_lines.sort_by_key(|ui_line| (ui_line.file_id.as_usize(), ui_line.line));
+        ui_lines
+    }
+
+    /// Events for the inputs with ids `new_input_ids`, just ingested into `databases`: one per new
+    /// input, then one per initial seed whose descendants cover new lines
+    pub fn events_for(&mut self, databases: &Databases, new_input_ids: &[InputId]) -> Vec<Event> {
+        let Some(first_new_input_id) = new_input_ids
+            .iter()
+            .map(|input_id| input_id.as_usize())
+            .min()
+        else {
+            return Vec::new();
+        };
+        let input_db = databases.get_inputs_db();
+        let mut events = Vec::new();
+        // BTreeMap, so that the events come in the same order for the same inputs
+        let mut new_initial_seed_lines: BTreeMap<(u32, u32), (InputId, HashSet<LineId>)> =
+            BTreeMap::new();
+
+        for input_id in new_input_ids {
+            // skipped inputs get no id
+            let Some(input_meta) = input_db.find_input_meta_for(input_id) else {
+                continue;
+            };
+            let fuzzer_configuration_id = input_meta.fuzzer_configuration;
+            let covered_lines = self
+                .covered_lines
+                .entry(fuzzer_configuration_id)
+                .or_default();
+            let new_lines: Vec<LineId> = input_meta
+                .source_line_coverage
+                .iter()
+                .filter(|line_id| covered_lines.insert(**line_id))
+                .copied()
+                .collect();
+
+            let initial_seed_ids: Vec<InputId> = if input_meta.is_initial_seed {
+                vec![*input_id]
+            } else {
+                input_db
+                    .get_initial_seed_parents_for(input_id, &fuzzer_configuration_id)
+                    .into_iter()
+                    .collect()
+            };
+            let mut initial_seed_fuzz_ids = Vec::new();
+            for initial_seed_id in initial_seed_ids {
+                let Some(initial_seed_meta) = input_db.find_input_meta_for(&initial_seed_id) else {
+                    continue;
+                };
+                initial_seed_fuzz_ids.push(initial_seed_meta.fuzz_input_id);
+                let initial_seed_coverage = self
+                    .initial_seed_coverage
+                    .entry(initial_seed_id)
+                    .or_insert_with(|| {
+                        Self::coverage_before(databases, &initial_seed_id, first_new_input_id)
+                    });
+                let (_, new_lines_of_seed) = new_initial_seed_lines
+                    .entry((fuzzer_configuration_id, initial_seed_meta.fuzz_input_id))
+                    .or_insert_with(|| (initial_seed_id, HashSet::new()));
+                for line_id in input_meta.source_line_coverage.iter() {
+                    if initial_seed_coverage.insert(*line_id) {
+                        new_lines_of_seed.insert(*line_id);
+                    }
+                }
+            }
+            initial_seed_fuzz_ids.sort();
+
+            events.push(Event::NewInput(UINewInput {
+                fuzzer_configuration_id,
+                input_id: input_meta.fuzz_input_id,
+                file_name_stem: input_meta.file_name_stem.clone(),
+                is_initial_seed: input_meta.is_initial_seed,
+                executed_on: input_meta.executed_on,
+                initial_seed_ids: initial_seed_fuzz_ids,
+                new_lines: Self::ui_lines(databases, new_lines),
+            }));
+        }
+
+        for ((fuzzer_configuration_id, initial_seed_id), (initial_seed_input_id, new_lines)) in
+            new_initial_seed_lines
+        {
+            if new_lines.is_empty() {
+                continue;
+            }
+            events.push(Event::InitialSeedCoverage(UIInitialSeedCoverage {
+                fuzzer_configuration_id,
+                initial_seed_id,
+                new_lines: Self::ui_lines(databases, new_lines),
+                lines_covered: self.initial_seed_coverage[&initial_seed_input_id].len(),
+            }));
+        }
+        events
+    }
+}
+
+/// The clients of `/events`
+#[derive(Default)]
+pub struct Broadcaster {
+    clients: Mutex<Vec<mpsc::Sender<Bytes>>>,
+}
+
+impl Broadcaster {
+    /// Registers a client, which first receives `greeting`
+    pub fn subscribe(&self, greeting: &Event) -> EventStream {
+        let (sender, receiver) = mpsc::channel(CLIENT_BUFFER);
+        // the buffer is empty
+        let _ = sender.try_send(greeting.to_sse());
+        self.clients.lock().unwrap().push(sender);
+        EventStream { receiver }
+    }
+
+    /// Sends `events` to every client, dropping those that disconnected or cannot keep up
+    pub fn broadcast(&self, events: &[Event]) {
+        let events: Vec<Bytes> = events.iter().map(Event::to_sse).collect();
+        self.send(&events);
+    }
+
+    /// A comment, so that proxies keep idle connections open and disconnected clients are noticed
+    pub fn ping(&self) {
+        self.send(&[Bytes::from_static(b": ping\n\n")]);
+    }
+
+    fn send(&self, messages: &[Bytes]) {
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|client| {
+            messages
+                .iter()
+                .all(|message| client.try_send(message.clone()).is_ok())
+        });
+    }
+
+    pub fn num_clients(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+}
+
+/// Body of an `/events` response
+pub struct EventStream {
+    receiver: mpsc::Receiver<Bytes>,
+}
+
+impl Stream for EventStream {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx).map(|message| message.map(Ok))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app_state::AppState,
+        test_fixtures::{lcov_target_config, lcov_target_databases, lcov_target_traces},
+    };
+    use actix_web::{body::MessageBody, http::header, test as actix_test, web, App};
+    use inputs_database::InputsDatabase;
+    use std::future::poll_fn;
+    use sut_database::SUT;
+
+    #[test]
+    fn test_events_for_new_inputs() {
+        let config = lcov_target_config();
+        let file_names = lcov_target_traces();
+
+        // the initial seed at startup
+        let (input_db, sut_db) = lcov_target_databases(&file_names[..1]);
+        let app_state = AppState::new(input_db, sut_db);
+        let mut tracker = CoverageTracker::new(&app_state.read());
+
+        // its child while running
+        let mut databases = app_state.write();
+        let num_inputs = databases.update(|input_db, sut_db| {
+            input_db
+                .add_inputs(&file_names[1..], &config, sut_db, 0, 1)
+                .unwrap();
+            input_db.post_process();
+            input_db.get_num_inputs()
+        });
+        let events = tracker.events_for(&databases, &[InputId::new(num_inputs)]);
+        assert_eq!(events.len(), 2);
+
+        let Event::NewInput(new_input) = &events[0] else {
+            panic!("{:?}", events[0]);
+        };
+        assert_eq!(new_input.input_id, 1);
+        assert_eq!(new_input.initial_seed_ids, vec![0]);
+        let new_lines: Vec<u32> = new_input.new_lines.iter().map(|l| l.line).collect();
+        // the loop of test and the function it calls run for the child only
+        assert!(new_lines.contains(&3));
+        assert!(new_lines.contains(&6));
+        assert!(!new_lines.contains(&22));
+        assert!(new_input.new_lines[0].file_name.ends_with("test.c"));
+
+        let Event::InitialSeedCoverage(coverage) = &events[1] else {
+            panic!("{:?}", events[1]);
+        };
+        assert_eq!(coverage.initial_seed_id, 0);
+        assert_eq!(coverage.new_lines, new_input.new_lines);
+        assert_eq!(coverage.lines_covered, 9 + new_lines.len());
+
+        // the same lines are not new twice
+        assert!(tracker.events_for(&databases, &[]).is_empty());
+        let events = tracker.events_for(&databases, &[InputId::new(num_inputs)]);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], Event::NewInput(new_input) if new_input.new_lines.is_empty()));
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let broadcaster = Broadcaster::default();
+        let reload = |generation| {
+            Event::Reload(UIReloadStatus {
+                generation,
+                watch_interval_ms: Some(5000),
+            })
+        };
+        let mut stream = broadcaster.subscribe(&reload(0));
+        let disconnected = broadcaster.subscribe(&reload(0));
+        drop(disconnected);
+        broadcaster.broadcast(&[reload(1)]);
+        assert_eq!(broadcaster.num_clients(), 1);
+
+        assert_eq!(
+            stream.receiver.try_recv().unwrap(),
+            "event: reload\ndata: {\"generation\":0,\"watch_interval_ms\":5000}\n\n"
+        );
+        assert_eq!(
+            stream.receiver.try_recv().unwrap(),
+            "event: reload\ndata: {\"generation\":1,\"watch_interval_ms\":5000}\n\n"
+        );
+        assert!(stream.receiver.try_recv().is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_events_endpoint() {
+        let app_state = web::Data::new(AppState::new(InputsDatabase::new(), SUT::new()));
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .configure(crate::configure_routes),
+        )
+        .await;
+        let request = actix_test::TestRequest::get().uri("/events").to_request();
+        let response = actix_test::call_service(&app, request).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+
+        let mut body = Box::pin(response.into_body());
+        assert_eq!(
+            poll_fn(|cx| body.as_mut().poll_next(cx))
+                .await
+                .unwrap()
+                .unwrap(),
+            "event: reload\ndata: {\"generation\":0,\"watch_interval_ms\":null}\n\n"
+        );
+        app_state.get_events().ping();
+        assert_eq!(
+            poll_fn(|cx| body.as_mut().poll_next(cx))
+                .await
+                .unwrap()
+                .unwrap(),
+            ": ping\n\n"
+        );
+    }
+}
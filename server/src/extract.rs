@@ -0,0 +1,364 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use config::{CoverageBackend, FuzzerConfig, QueueNameFormat, TracerConfig, UserConfig};
+
+/// Result of recording the trace of one input
+#[derive(Debug, PartialEq, Eq)]
+enum TraceOutcome {
+    Traced,
+    UpToDate,
+    TimedOut,
+    Failed(String),
+}
+
+/// Name of the trace recorded for the input `input_path`, as expected by the queue name parsers:
+/// the file name of the input with `,` replaced by `::`, which would otherwise split the
+/// arguments of the drcov plugin.
+pub fn trace_file_name(input_path: &Path) -> String {
+    let file_name = input_path
+        .file_name()
+        .map_or_else(String::new, |f| f.to_string_lossy().into_owned());
+    format!("{}.trace", file_name.replace(',', "::"))
+}
+
+/// Arguments of the target for the input `input_path`, and whether the input is passed on stdin
+fn target_arguments(tracer: &TracerConfig, input_path: &Path) -> (Vec<String>, bool) {
+    let input_path = input_path.to_string_lossy();
+    let uses_stdin = !tracer.target_arguments.iter().any(|a| a.contains("@@"));
+    let arguments = tracer
+        .target_arguments
+        .iter()
+        .map(|a| a.replace("@@", &input_path))
+        .collect();
+    (arguments, uses_stdin)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// A trace is up to date if it is not empty and newer than both its input and the target
+fn is_up_to_date(trace_path: &Path, input_path: &Path, target_path: &Path) -> bool {
+    let Ok(trace_metadata) = fs::metadata(trace_path) else {
+        return false;
+    };
+    let Ok(trace_modified) = trace_metadata.modified() else {
+        return false;
+    };
+    trace_metadata.len() > 0
+        && [input_path, target_path]
+            .iter()
+            .all(|p| modified(p).is_some_and(|m| m <= trace_modified))
+}
+
+fn list_inputs(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut inputs: Vec<PathBuf> = fs::read_dir(directory)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Inputs of a fuzzer configuration, including the initial seeds kept apart from the corpus
+fn inputs_of(fuzzer_config: &FuzzerConfig) -> io::Result<Vec<PathBuf>> {
+    let mut inputs = list_inputs(Path::new(&fuzzer_config.inputs_directory_path))?;
+    if let QueueNameFormat::LibFuzzer {
+        seed_corpus_path, ..
+    }
+    | QueueNameFormat::HonggfuzzOutput {
+        seed_corpus_path, ..
+    } = &fuzzer_config.queue_name_format
+    {
+        inputs.extend(list_inputs(Path::new(seed_corpus_path))?);
+    }
+    Ok(inputs)
+}
+
+/// Argument of `afl-qemu-trace` loading the drcov plugin, which writes the trace to `trace_path`
+/// and, given `full_trace_argument`, lists every executed basic block
+fn plugin_argument(
+    tracer: &TracerConfig,
+    full_trace_argument: Option<&str>,
+    trace_path: &Path,
+) -> String {
+    let mut argument = format!(
+        "{},arg=filename={}",
+        tracer.drcov_plugin_path,
+        trace_path.to_string_lossy()
+    );
+    if let Some(full_trace_argument) = full_trace_argument {
+        argument.push_str(",arg=");
+        argument.push_str(full_trace_argument);
+    }
+    argument
+}
+
+fn trace_input(
+    tracer: &TracerConfig,
+    full_trace_argument: Option<&str>,
+    target_path: &Path,
+    input_path: &Path,
+    trace_path: &Path,
+) -> TraceOutcome {
+    if is_up_to_date(trace_path, input_path, target_path) {
+        return TraceOutcome::UpToDate;
+    }
+
+    let (arguments, uses_stdin) = target_arguments(tracer, input_path);
+    let stdin = if uses_stdin {
+        match File::open(input_path) {
+            Ok(f) => Stdio::from(f),
+            Err(e) => return TraceOutcome::Failed(e.to_string()),
+        }
+    } else {
+        Stdio::null()
+    };
+    let spawned = Command::new(&tracer.qemu_trace_path)
+        .arg("-plugin")
+        .arg(plugin_argument(tracer, full_trace_argument, trace_path))
+        .arg("--")
+        .arg(target_path)
+        .args(arguments)
+        .stdin(stdin)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => return TraceOutcome::Failed(format!("{}: {}", tracer.qemu_trace_path, e)),
+    };
+
+    let deadline = Instant::now() + Duration::from_millis(tracer.timeout_ms);
+    loop {
+        match child.try_wait() {
+            // crashing inputs still leave a trace of what they covered
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(5)),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                // a partial trace would look up to date on the next run
+                let _ = fs::remove_file(trace_path);
+                return TraceOutcome::TimedOut;
+            }
+            Err(e) => return TraceOutcome::Failed(e.to_string()),
+        }
+    }
+
+    if fs::metadata(trace_path).is_ok_and(|m| m.len() > 0) {
+        TraceOutcome::Traced
+    } else {
+        TraceOutcome::Failed("no trace written".to_string())
+    }
+}
+
+/// Records the drcov trace of every input of every fuzzer configuration into its
+/// `traces_directory_path`, skipping inputs whose trace is up to date. Full traces are recorded
+/// for the `DrCovFullTrace` backend. Returns the number of inputs that could not be traced.
+pub fn extract_traces(config: &UserConfig) -> io::Result<usize> {
+    let tracer = &config.tracer;
+    let full_trace_argument = match config.target_info.coverage_backend {
+        CoverageBackend::DrCov => None,
+        CoverageBackend::DrCovFullTrace => match &tracer.full_trace_plugin_argument {
+            Some(full_trace_argument) => Some(full_trace_argument.as_str()),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "full traces need the full_trace_plugin_argument of the tracer",
+                ))
+            }
+        },
+        coverage_backend => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "traces of the {:?} coverage backend are not recorded by afl-qemu-trace",
+                    coverage_backend
+                ),
+            ))
+        }
+    };
+    let target_path = Path::new(&config.target_info.target_path);
+    let num_workers = match tracer.jobs {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        jobs => jobs,
+    };
+
+    let mut failures = 0;
+    for fuzzer_info in &config.fuzzer_infos {
+        log::info!("fuzzer: {:?}", fuzzer_info.fuzzer_configuration);
+        let inputs = inputs_of(fuzzer_info)?;
+        let traces_directory = Path::new(&fuzzer_info.traces_directory_path);
+        let next_index = AtomicUsize::new(0);
+
+        let mut outcomes: Vec<(usize, TraceOutcome)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..num_workers.clamp(1, inputs.len().max(1)))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut outcomes = Vec::new();
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::Relaxed);
+                            let Some(input_path) = inputs.get(index) else {
+                                break;
+                            };
+                            let trace_path = traces_directory.join(trace_file_name(input_path));
+                            outcomes.push((
+                                index,
+                                trace_input(
+                                    tracer,
+                                    full_trace_argument,
+                                    target_path,
+                                    input_path,
+                                    &trace_path,
+                                ),
+                            ));
+                        }
+                        outcomes
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+        outcomes.sort_by_key(|(index, _)| *index);
+
+        let (mut traced, mut up_to_date) = (0, 0);
+        for (index, outcome) in outcomes {
+            match outcome {
+                TraceOutcome::Traced => traced += 1,
+                TraceOutcome::UpToDate => up_to_date += 1,
+                TraceOutcome::TimedOut => {
+                    failures += 1;
+                    log::warn!("timed out: {:?}", inputs[index]);
+                }
+                TraceOutcome::Failed(reason) => {
+                    failures += 1;
+                    log::warn!("failed: {:?}: {}", inputs[index], reason);
+                }
+            }
+        }
+        log::info!(
+            "total inputs: {}, traced: {}, up to date: {}",
+            inputs.len(),
+            traced,
+            up_to_date
+        );
+    }
+
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_trace_file_name() {
+        assert_eq!(
+            trace_file_name(Path::new(
+                "/out/queue/id:000001,src:000000,time:12,execs:40,op:havoc"
+            )),
+            "id:000001::src:000000::time:12::execs:40::op:havoc.trace"
+        );
+    }
+
+    #[test]
+    fn test_target_arguments() {
+        let mut tracer = TracerConfig {
+            target_arguments: vec!["-a".to_string(), "--file=@@".to_string()],
+            ..TracerConfig::default()
+        };
+        assert_eq!(
+            target_arguments(&tracer, Path::new("/out/queue/input")),
+            (
+                vec!["-a".to_string(), "--file=/out/queue/input".to_string()],
+                false
+            )
+        );
+
+        tracer.target_arguments = vec!["-".to_string()];
+        assert_eq!(
+            target_arguments(&tracer, Path::new("/out/queue/input")),
+            (vec!["-".to_string()], true)
+        );
+    }
+
+    #[test]
+    fn test_plugin_argument() {
+        let tracer = TracerConfig::default();
+        let trace_path = Path::new("/traces/input.trace");
+        assert_eq!(
+            plugin_argument(&tracer, None, trace_path),
+            "libdrcov.so,arg=filename=/traces/input.trace"
+        );
+        assert_eq!(
+            plugin_argument(&tracer, Some("fulltrace=on"), trace_path),
+            "libdrcov.so,arg=filename=/traces/input.trace,arg=fulltrace=on"
+        );
+    }
+
+    #[test]
+    fn test_extract_backends() {
+        let mut config = UserConfig::new();
+        config.target_info.coverage_backend = CoverageBackend::DrCovFullTrace;
+        assert_eq!(
+            extract_traces(&config).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        config.tracer.full_trace_plugin_argument = Some("fulltrace=on".to_string());
+        assert_eq!(extract_traces(&config).unwrap(), 0);
+
+        config.target_info.coverage_backend = CoverageBackend::LlvmCovJson;
+        assert_eq!(
+            extract_traces(&config).unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+    }
+
+    #[test]
+    fn test_trace_up_to_date() {
+        let directory = env::temp_dir().join("seedui_test_trace_up_to_date");
+        fs::create_dir_all(&directory).unwrap();
+        let input_path = directory.join("input");
+        let trace_path = directory.join("input.trace");
+        fs::write(&input_path, "input").unwrap();
+        let _ = fs::remove_file(&trace_path);
+        assert!(!is_up_to_date(&trace_path, &input_path, &input_path));
+
+        fs::write(&trace_path, "").unwrap();
+        assert!(!is_up_to_date(&trace_path, &input_path, &input_path));
+
+        fs::write(&trace_path, "DRCOV VERSION: 2\n").unwrap();
+        assert!(is_up_to_date(&trace_path, &input_path, &input_path));
+
+        // a missing tracer is reported, not fatal
+        let tracer = TracerConfig {
+            qemu_trace_path: directory
+                .join("does_not_exist")
+                .to_string_lossy()
+                .into_owned(),
+            ..TracerConfig::default()
+        };
+        fs::remove_file(&trace_path).unwrap();
+        assert!(matches!(
+            trace_input(&tracer, None, &input_path, &input_path, &trace_path),
+            TraceOutcome::Failed(_)
+        ));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use std::{collections::HashMap, fmt::Write};
+
+use custom_types::{InputId, LineId};
+use inputs_database::InputsDatabase;
+use sut_database::SUT;
+
+/// Inputs whose coverage is exported or aggregated: the given inputs, or else an initial seed with
+/// all its descendants, or else all inputs of the fuzzer configuration including the initial
+/// seeds.
+pub fn select_inputs(
+    input_db: &InputsDatabase,
+    fuzzer_configuration_id: &u32,
+    initial_seed_id: Option<u32>,
+    fuzz_input_ids: Option<&[u32]>,
+) -> Result<Vec<InputId>, String> {
+    if let Some(fuzz_input_ids) = fuzz_input_ids {
+        return fuzz_input_ids
+            .iter()
+            .map(|fuzz_input_id| {
+                input_db
+                    .find_input_id_for(fuzzer_configuration_id, fuzz_input_id)
+                    .copied()
+                    .ok_or_else(|| format!("unknown input {}", fuzz_input_id))
+            })
+            .collect();
+    }
+
+    let Some(initial_seed_id) = initial_seed_id else {
+        return Ok(input_db.get_all_input_ids_with_initial_seeds_for(fuzzer_configuration_id));
+    };
+    let initial_seed = input_db
+        .find_input_id_for(fuzzer_configuration_id, &initial_seed_id)
+        .filter(|input_id| {
+            input_db
+                .find_input_meta_for(input_id)
+                .is_some_and(|input_meta| input_meta.is_initial_seed)
+        })
+        .ok_or_else(|| format!("unknown initial seed {}", initial_seed_id))?;
+    let mut input_ids = vec![*initial_seed];
+    if input_db.has_children_for(fuzzer_configuration_id, &initial_seed_id) {
+        let mut children = input_db
+            .get_all_children_input_ids_for(fuzzer_configuration_id, &vec![initial_seed_id]);
+        children.sort_by_key(|input_id| input_id.as_usize());
+        input_ids.extend(children);
+    }
+    Ok(input_ids)
+}
+
+/// Writes `line_hit_counts` as an LCOV tracefile with one record for every source file of
+/// `sut_db`. All code lines are listed, so that uncovered ones count towards the totals of
+/// `genhtml` and other tools.
+pub fn write_lcov(test_name: &str, line_hit_counts: &HashMap<LineId, u64>, sut_db: &SUT) -> String {
+    // test names may only contain letters, digits and underscores
+    let test_name: String = test_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    let mut files: Vec<_> = sut_db.get_all_file_meta().iter().collect();
+    files.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+
+    let mut lcov = String::new();
+    for (file_id, file_meta) in files {
+        let mut lines: Vec<u32> = sut_db
+            .get_all_lines(*file_id)
+            .into_iter()
+            .filter(|line_meta| !line_meta.is_comment)
+            .map(|line_meta| line_meta.line_num)
+            .collect();
+        lines.sort();
+
+        let _ = writeln!(lcov, "TN:{}", test_name);
+        let _ = writeln!(lcov, "SF:{}", file_meta.name);
+        let mut lines_hit = 0;
+        for line in lines.iter() {
+            let count = line_hit_counts
+                .get(&LineId::new(*file_id, *line))
+                .copied()
+                .unwrap_or(0);
+            if count > 0 {
+                lines_hit += 1;
+            }
+            let _ = writeln!(lcov, "DA:{},{}", line, count);
+        }
+        let _ = writeln!(lcov, "LF:{}", lines.len());
+        let _ = writeln!(lcov, "LH:{}", lines_hit);
+        lcov.push_str("end_of_record\n");
+    }
+
+    lcov
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::lcov_target_config;
+
+    #[test]
+    fn test_write_lcov() {
+        let config = lcov_target_config();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+        let test_c = format!("{}/test.c", config.target_source_code_path);
+        let file_id = sut_db.parse_file(&test_c).unwrap().unwrap();
+
+        let line_hit_counts =
+            HashMap::from([(LineId::new(file_id, 3), 2), (LineId::new(file_id, 22), 1)]);
+        let lcov = write_lcov("afl++ run 1", &line_hit_counts, &sut_db);
+        let records: Vec<&str> = lcov.lines().collect();
+        assert_eq!(records[0], "TN:afl___run_1");
+        assert_eq!(records[1], format!("SF:{}", test_c));
+        assert!(records.contains(&"DA:3,2"));
+        assert!(records.contains(&"DA:5,0"));
+        // comments and empty lines are no code
+        assert!(!records.iter().any(|r| r.starts_with("DA:2,")));
+        assert!(records.contains(&"LH:2"));
+        assert_eq!(records.last(), Some(&"end_of_record"));
+    }
+}
@@ -1,139 +1,378 @@
 use std::{
     env,
-    fs::{self, read_dir},
-    path::Path,
+    net::IpAddr,
+    path::{Path, PathBuf},
     process::exit,
+    thread,
+    time::Duration,
 };
 
-use actix_cors::Cors;
 use actix_web::{
+    middleware::from_fn,
+    rt,
     web::{self},
     App, HttpServer,
 };
-use glob::glob;
 
+mod access;
 mod app_state;
+mod cli;
+mod events;
+mod extract;
+mod lcov;
 mod responders;
+mod snapshot;
+#[cfg(test)]
+mod test_fixtures;
+mod tls;
+mod watch;
 
-use inputs_database::InputsDatabase;
+use inputs_database::{IngestionError, InputsDatabase};
 use sut_database::SUT;
-use config::UserConfig;
+use config::{LogLevel, UserConfig};
+use log::LevelFilter;
+
+fn build_databases(config: &UserConfig) -> Result<(InputsDatabase, SUT), IngestionError> {
+    let mut input_db: InputsDatabase = InputsDatabase::new();
+    let mut sut_db = SUT::new();
+    let num_workers = thread::available_parallelism().map_or(1, |n| n.get());
+
+    sut_db.parse_config(&config.target_info);
+    // source based coverage backends may come without the target binary
+    for module_path in config.target_info.module_paths() {
+        if !Path::new(module_path).is_file() {
+            continue;
+        }
+        // before any coverage is recorded, lines without code are never counted as covered
+        match sut_db.parse_code_lines(module_path) {
+            Ok(num_files) => log::info!("source files in {}: {}", module_path, num_files),
+            Err(e) => log::warn!("unable to classify the lines with code: {}", e),
+        }
+        match sut_db.parse_functions(module_path) {
+            Ok(num_functions) => log::info!("functions in {}: {}", module_path, num_functions),
+            Err(e) => log::warn!("unable to index the functions: {}", e),
+        }
+    }
+    for fuzzer_info in &config.fuzzer_infos {
+        log::info!("fuzzer: {:?}", fuzzer_info.fuzzer_configuration);
+        input_db.add_fuzzer_configuration(fuzzer_info);
+
+        // initial seeds and derived inputs are told apart by the queue name parser of the
+        // fuzzer configuration
+        let file_names = watch::trace_file_names(&fuzzer_info.traces_directory_path)
+            .expect("Failed to read trace files in the directory");
+        log::info!("total trace files: {}", file_names.len());
+
+        input_db.add_inputs(
+            &file_names,
+            &config.target_info,
+            &mut sut_db,
+            fuzzer_info.fuzzer_configuration_id,
+            num_workers,
+        )?;
+    }
+
+    input_db.post_process();
+
+    Ok((input_db, sut_db))
+}
+
+fn print_skipped_inputs(input_db: &InputsDatabase) {
+    let skipped_inputs = input_db.get_skipped_inputs();
+    if skipped_inputs.is_empty() {
+        return;
+    }
+
+    log::warn!("skipped {} input(s):", skipped_inputs.len());
+    for skipped_input in skipped_inputs.iter() {
+        log::warn!(
+            "[{}] {}: {}",
+            skipped_input.fuzzer_configuration_id,
+            skipped_input.file_name,
+            skipped_input.reason
+        );
+    }
+}
+
+fn load_config(config_path: &Path) -> UserConfig {
+    match UserConfig::parse(&config_path.to_string_lossy()) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("Unable to load configuration {:?}: {}", config_path, e);
+            exit(1);
+        }
+    }
+}
+
+fn extract_traces(config: &UserConfig) -> ! {
+    match extract::extract_traces(config) {
+        Ok(0) => exit(0),
+        Ok(failures) => {
+            log::error!("unable to trace {} input(s)", failures);
+            exit(1);
+        }
+        Err(e) => {
+            log::error!("unable to extract the traces: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Exits with the problems of `config` before anything is ingested, if there are any
+fn exit_on_config_problems(config_path: &Path, config: &UserConfig) {
+    let problems = cli::config_problems(config);
+    if problems.is_empty() {
+        return;
+    }
+    eprintln!("Configuration {:?} is invalid:", config_path);
+    for problem in problems.iter() {
+        eprintln!("\t{}", problem);
+    }
+    exit(1);
+}
+
+fn check_config(config_path: &Path, config: &UserConfig) -> ! {
+    exit_on_config_problems(config_path, config);
+    println!("Configuration {:?} is valid", config_path);
+    exit(0);
+}
+
+fn init_logger(log_level: LogLevel) {
+    let level_filter = match log_level {
+        LogLevel::Off => LevelFilter::Off,
+        LogLevel::Error => LevelFilter::Error,
+        LogLevel::Warn => LevelFilter::Warn,
+        LogLevel::Info => LevelFilter::Info,
+        LogLevel::Debug => LevelFilter::Debug,
+        LogLevel::Trace => LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(level_filter)
+        .format_target(false)
+        .init();
+}
+
+fn configure_routes(config: &mut web::ServiceConfig) {
+    config
+        .route("/fuzzer_info", web::get().to(responders::get_fuzzer_info))
+        .route(
+            "/reload_status",
+            web::get().to(responders::get_reload_status),
+        )
+        .route("/events", web::get().to(responders::get_events))
+        .route(
+            "/events_token",
+            web::post().to(responders::get_events_token),
+        )
+        .route(
+            "/line_coverage",
+            web::post().to(responders::get_line_coverage_over_time),
+        )
+        .route("/sut", web::get().to(responders::get_sut))
+        .route(
+            "/skipped_inputs",
+            web::get().to(responders::get_skipped_inputs),
+        )
+        .route(
+            "/ingestion_report",
+            web::get().to(responders::get_ingestion_report),
+        )
+        .route(
+            "/sut_file_info",
+            web::get().to(responders::get_sut_file_id_name_map),
+        )
+        .route(
+            "/input_clusters",
+            web::post().to(responders::get_all_input_clusters),
+        )
+        .route(
+            "/compare_inputs",
+            web::post().to(responders::get_inputs_comparison),
+        )
+        .route(
+            "/initial_seeds_line_coverage_for_file",
+            web::post().to(responders::get_initial_seeds_line_coverage_for_file),
+        )
+        .route(
+            "/line_coverage_for_file",
+            web::post().to(responders::get_line_coverage_for_file),
+        )
+        .route(
+            "/edge_coverage_for_file",
+            web::post().to(responders::get_edge_coverage_for_file),
+        )
+        .route(
+            "/line_hit_counts_for_file",
+            web::post().to(responders::get_line_hit_counts_for_file),
+        )
+        .route(
+            "/heat_map_for_file",
+            web::post().to(responders::get_heat_map_for_file),
+        )
+        .route("/lcov", web::post().to(responders::get_lcov_export))
+        .route("/functions", web::get().to(responders::get_functions))
+        .route(
+            "/function_coverage",
+            web::post().to(responders::get_function_coverage),
+        )
+        .route(
+            "/initial_seed_timeline",
+            web::post().to(responders::get_initial_seed_timeline),
+        );
+}
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        println!("Usage: server path/to/config.ron");
-        exit(1);
+    let args = match cli::Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            println!("{}\n{}", e, cli::USAGE);
+            exit(1);
+        }
+    };
+    if args.help {
+        println!("{}", cli::USAGE);
+        exit(0);
     }
 
-    let config_arg = Path::new(&args[1]);
-    if !config_arg.exists() || config_arg.is_dir() {
+    let config_path = args.config_path.as_path();
+    if !config_path.exists() || config_path.is_dir() {
         println!(
-            "Argument {:?} is not a configuration file.\nUsage: server path/to/config.ron",
-            &args[1]
+            "Argument {:?} is not a configuration file.\n{}",
+            config_path,
+            cli::USAGE
         );
         exit(1);
     }
 
-    let addr = "127.0.0.1:8080";
-    let config = UserConfig::parse(config_arg.to_str().unwrap());
-    let mut input_db: InputsDatabase = InputsDatabase::new();
-    let mut sut_db = SUT::new();
-
-    sut_db.parse_config(&config.target_info);
-    for fuzzer_info in &config.fuzzer_infos {
-        println!("Fuzzer: {:?}", fuzzer_info.fuzzer_configuration);
-        input_db.add_fuzzer_configuration(fuzzer_info);
+    let mut config = load_config(config_path);
+    args.apply_to(&mut config.server);
+    if args.check_config {
+        check_config(config_path, &config);
+    }
+    init_logger(config.server.log_level);
+    if args.extract {
+        extract_traces(&config);
+    }
+    // the server section of the configuration file is not checked by the argument parser
+    exit_on_config_problems(config_path, &config);
+    // before the traces are ingested, which may take a while
+    let tls_config = config
+        .server
+        .tls
+        .as_ref()
+        .map(|tls| match tls::server_config(tls) {
+            Ok(tls_config) => tls_config,
+            Err(e) => {
+                log::error!("unable to set up TLS: {}", e);
+                exit(1);
+            }
+        });
 
-        let mut total_initial_seeds = 0;
-        for entry in glob(&format!("{}/id:*", fuzzer_info.traces_directory_path))
-            .expect("Failed to read glob pattern")
-        {
-            sut_db = input_db.add_input(
-                entry.unwrap().to_str().unwrap(),
-                &config.target_info,
-                sut_db,
-                fuzzer_info.fuzzer_configuration_id,
-            );
-            total_initial_seeds += 1;
+    let snapshot_path = match &config.server.snapshot_path {
+        Some(snapshot_path) => PathBuf::from(snapshot_path),
+        None => snapshot::snapshot_path_for(config_path),
+    };
+    let fingerprint = snapshot::compute_fingerprint(&config);
+    let (input_db, sut_db) = match snapshot::load(&snapshot_path, fingerprint) {
+        Some(databases) => {
+            log::info!("loaded snapshot {:?}", snapshot_path);
+            databases
         }
-        println!("\ttotal initial seeds: {:?}", total_initial_seeds);
-
-        let mut total_trace_files = 0;
-        for entry in read_dir(&fuzzer_info.traces_directory_path)
-            .expect("Failed to read trace files in the directory")
-        {
-            let path = entry.unwrap().path();
-            if path.is_file() {
-                if path.file_name().unwrap().to_str().unwrap().contains("orig") {
-                    continue;
-                }
-
-                if let Ok(absolute_path) = fs::canonicalize(&path) {
-                    sut_db = input_db.add_input(
-                        absolute_path.to_str().unwrap(),
-                        &config.target_info,
-                        sut_db,
-                        fuzzer_info.fuzzer_configuration_id,
-                    );
-                    total_trace_files += 1;
+        None => {
+            let (input_db, sut_db) = match build_databases(&config) {
+                Ok(databases) => databases,
+                Err(e) => {
+                    log::error!("unable to ingest the traces: {}", e);
+                    exit(1);
                 }
+            };
+            if let Err(e) = snapshot::store(&snapshot_path, fingerprint, &input_db, &sut_db) {
+                log::error!("unable to write snapshot {:?}: {:?}", snapshot_path, e);
             }
+            (input_db, sut_db)
         }
-        println!("\ttotal trace files: {:?}", total_trace_files);
-    }
+    };
+    print_skipped_inputs(&input_db);
 
-    input_db.post_process();
+    let server_config = config.server.clone();
+    let scheme = match tls_config {
+        Some(_) => "https",
+        None => "http",
+    };
+    // IPv6 addresses are put in brackets in URLs
+    if server_config.bind_address.contains(':') {
+        log::info!(
+            "listening on {}://[{}]:{}",
+            scheme,
+            server_config.bind_address,
+            server_config.port
+        );
+    } else {
+        log::info!(
+            "listening on {}://{}:{}",
+            scheme,
+            server_config.bind_address,
+            server_config.port
+        );
+    }
 
-    println!("https://{}", addr);
-
-    HttpServer::new(move || {
-        // TODO: I know we should never do this but first lets get this app up and running!
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_header()
-            .allowed_methods(vec!["GET", "POST"]);
-
-        App::new()
-            .wrap(cors)
-            .app_data(web::Data::new(app_state::AppState::new(
-                input_db.clone(),
-                sut_db.clone(),
-            )))
-            .route("/fuzzer_info", web::get().to(responders::get_fuzzer_info))
-            .route(
-                "/line_coverage",
-                web::post().to(responders::get_line_coverage_over_time),
-            )
-            .route("/sut", web::get().to(responders::get_sut))
-            .route(
-                "/sut_file_info",
-                web::get().to(responders::get_sut_file_id_name_map),
-            )
-            .route(
-                "/input_clusters",
-                web::post().to(responders::get_all_input_clusters),
-            )
-            .route(
-                "/compare_inputs",
-                web::post().to(responders::get_inputs_comparison),
-            )
-            .route(
-                "/initial_seeds_line_coverage_for_file",
-                web::post().to(responders::get_initial_seeds_line_coverage_for_file),
-            )
-            .route(
-                "/line_coverage_for_file",
-                web::post().to(responders::get_line_coverage_for_file),
-            )
-            .route(
-                "/initial_seed_timeline",
-                web::post().to(responders::get_initial_seed_timeline),
-            )
-    })
-    .bind(addr)?
-    .workers(1)
-    .run()
-    .await
+    let is_loopback = server_config
+        .bind_address
+        .parse::<IpAddr>()
+        .is_ok_and(|address| address.is_loopback());
+    if server_config.auth.is_none() && !is_loopback {
+        log::warn!("no authentication configured, anyone who can reach the server can use it");
+    }
+    let required_authorization = server_config
+        .auth
+        .as_ref()
+        .map(|auth| web::Data::new(access::RequiredAuthorization::new(auth)));
+    let events_tokens = web::Data::new(access::EventsTokens::new(Duration::from_secs(60)));
+    // shared by all workers, so that the traces ingested while running are seen by every request
+    let app_state = web::Data::new(
+        app_state::AppState::new(input_db, sut_db).watching_every(server_config.watch_interval_ms),
+    );
+    if let Some(watch_interval_ms) = server_config.watch_interval_ms {
+        log::info!(
+            "watching the traces directories every {} ms",
+            watch_interval_ms
+        );
+        watch::spawn(
+            config,
+            app_state.clone(),
+            Duration::from_millis(watch_interval_ms),
+        );
+    }
+    let pinged_app_state = app_state.clone();
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(Duration::from_secs(15));
+        loop {
+            interval.tick().await;
+            pinged_app_state.get_events().ping();
+        }
+    });
+    let server = HttpServer::new(move || {
+        // the outermost middleware is wrapped last, preflight requests carry no credentials
+        let mut app = App::new()
+            .wrap(from_fn(access::authorize))
+            .wrap(access::cors(&server_config.allowed_origins))
+            .app_data(app_state.clone())
+            .app_data(events_tokens.clone());
+        if let Some(required_authorization) = &required_authorization {
+            app = app.app_data(required_authorization.clone());
+        }
+        app.configure(configure_routes)
+    });
+    let address = (server_config.bind_address.as_str(), server_config.port);
+    let server = match tls_config {
+        Some(tls_config) => server.bind_rustls_0_23(address, tls_config)?,
+        None => server.bind(address)?,
+    };
+    server
+        .workers(server_config.workers)
+        // clients of /events never finish their requests
+        .shutdown_timeout(5)
+        .run()
+        .await
 }
@@ -1,11 +1,19 @@
-use std::{collections::HashMap, fmt, path::PathBuf, sync::OnceLock};
-
-use actix_web::{web, Responder};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use actix_web::{http::header, web, HttpResponse, Responder};
 use chrono::{Local, NaiveDateTime, TimeZone};
 use serde::{Serialize, Deserialize};
 
-use crate::app_state::AppState;
+use crate::{access::EventsTokens, app_state::AppState, events::Event, lcov};
 use custom_types::*;
+use inputs_database::{
+    AmbiguousParent, EdgeCoverage, FunctionCoverage, LineHeat, OrphanedInput, SkippedInput,
+};
 
 static NORMALIZED_STARTTIME: OnceLock<i64> = OnceLock::new();
 fn get_normalized_starttime_millis() -> i64 {
@@ -57,11 +65,12 @@ impl fmt::Debug for UIFuzzerInfo {
 }
 
 pub async fn get_fuzzer_info(data: web::Data<AppState>) -> impl Responder {
-    println!("GET /fuzzer_info");
+    log::info!("GET /fuzzer_info");
+    let databases = data.read();
     let mut response: Vec<UIFuzzerInfo> = Vec::new();
-    let fuzzer_infos = data.get_inputs_db().get_all_fuzzer_configurations();
+    let fuzzer_infos = databases.get_inputs_db().get_all_fuzzer_configurations();
     for (fuzzer_conf_id, fuzzer_config) in fuzzer_infos.iter() {
-        let fuzzer_min_max_times = data
+        let fuzzer_min_max_times = databases
             .get_inputs_db()
             .get_run_times_for_fuzzer_id(fuzzer_conf_id);
         let min_time: i64 = fuzzer_min_max_times.0;
@@ -70,11 +79,11 @@ pub async fn get_fuzzer_info(data: web::Data<AppState>) -> impl Responder {
         let mut current_response = UIFuzzerInfo {
             fuzzer_configuration_id: *fuzzer_conf_id,
             fuzzer_configuration_name: fuzzer_config.fuzzer_configuration.clone(),
-            total_initial_seeds: data
+            total_initial_seeds: databases
                 .get_inputs_db()
                 .get_all_initial_seeds_for_fuzzer_id(fuzzer_conf_id)
                 .len(),
-            total_inputs: data
+            total_inputs: databases
                 .get_inputs_db()
                 .get_all_inputs_for_fuzzer_id(fuzzer_conf_id)
                 .len(),
@@ -82,21 +91,21 @@ pub async fn get_fuzzer_info(data: web::Data<AppState>) -> impl Responder {
             run_time: (max_time - min_time) as f32 / (1000 * 60 * 60) as f32,
         };
 
-        for initial_seed_id in data
+        for initial_seed_id in databases
             .get_inputs_db()
             .get_all_initial_seeds_meta_info(fuzzer_conf_id)
             .iter()
         {
-            if data
+            if databases
                 .get_inputs_db()
                 .has_children_for(fuzzer_conf_id, &initial_seed_id.1.fuzz_input_id)
             {
-                let children = data.get_inputs_db().get_all_children_input_ids_for(
+                let children = databases.get_inputs_db().get_all_children_input_ids_for(
                     fuzzer_conf_id,
                     &vec![initial_seed_id.1.fuzz_input_id],
                 );
                 for child in children.iter() {
-                    let child_meta = data.get_inputs_db().get_inputs_meta_info_for(child);
+                    let child_meta = databases.get_inputs_db().get_inputs_meta_info_for(child);
                     current_response
                         .initial_seeds_children_input_id_map
                         .entry(initial_seed_id.1.fuzz_input_id)
@@ -111,6 +120,57 @@ pub async fn get_fuzzer_info(data: web::Data<AppState>) -> impl Responder {
     serde_json::to_string(&response)
 }
 
+#[derive(Clone, Debug, Serialize)]
+pub struct UIReloadStatus {
+    /// Changes whenever new traces were ingested, the UI fetches everything again
+    pub generation: u64,
+    /// How often the server checks for new traces, if at all
+    pub watch_interval_ms: Option<u64>,
+}
+
+pub fn reload_status(app_state: &AppState) -> UIReloadStatus {
+    UIReloadStatus {
+        generation: app_state.read().get_generation(),
+        watch_interval_ms: app_state.get_watch_interval_ms(),
+    }
+}
+
+pub async fn get_reload_status(data: web::Data<AppState>) -> impl Responder {
+    log::debug!("GET /reload_status");
+    serde_json::to_string(&reload_status(&data))
+}
+
+/// Server-Sent Events of the inputs ingested while running, see `events::Event`
+pub async fn get_events(data: web::Data<AppState>) -> impl Responder {
+    log::info!("GET /events");
+    let greeting = Event::Reload(reload_status(&data));
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(data.get_events().subscribe(&greeting))
+}
+
+#[derive(Serialize)]
+struct EventsToken {
+    token: String,
+    expires_in_ms: u128,
+}
+
+/// A short-lived token for `/events?token=…`, see `access::EventsTokens`
+pub async fn get_events_token(tokens: web::Data<EventsTokens>) -> impl Responder {
+    log::info!("POST /events_token");
+    match tokens.issue() {
+        Some(token) => HttpResponse::Ok().json(EventsToken {
+            token,
+            expires_in_ms: tokens.get_lifetime().as_millis(),
+        }),
+        None => {
+            log::error!("unable to generate a token for /events");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 #[derive(Clone, Serialize)]
 pub struct UIOverviewInfo {
     pub input_id: u32,
@@ -132,14 +192,18 @@ pub async fn get_line_coverage_over_time(
     interesting_lines: web::Json<HashMap<usize, Vec<u32>>>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    println!("POST /line_coverage {:?}", interesting_lines);
-    let all_inputs = data.get_inputs_db().get_all_inputs_meta_info();
+    log::info!("POST /line_coverage {:?}", interesting_lines);
+    let databases = data.read();
+    let all_inputs = databases.get_inputs_db().get_all_inputs_meta_info();
     let mut ret: HashMap<u32, HashMap<i64, UIOverviewInfo>> = HashMap::new();
 
-    for (fuzzer_configuration_id, _) in data.get_inputs_db().get_all_fuzzer_configurations().iter()
+    for (fuzzer_configuration_id, _) in databases
+        .get_inputs_db()
+        .get_all_fuzzer_configurations()
+        .iter()
     {
         ret.insert(*fuzzer_configuration_id, HashMap::new());
-        let fuzzer_min_max_times = data
+        let fuzzer_min_max_times = databases
             .get_inputs_db()
             .get_run_times_for_fuzzer_id(fuzzer_configuration_id);
         let min_start_time: i64 = fuzzer_min_max_times.0;
@@ -193,23 +257,28 @@ impl UIFileInfo {
 }
 
 pub async fn get_sut(data: web::Data<AppState>) -> impl Responder {
-    println!("GET /sut");
+    log::info!("GET /sut");
+    let databases = data.read();
 
     let mut response: Vec<UIFileInfo> = Vec::new();
-    let fuzzer_configurations = data.get_inputs_db().get_all_fuzzer_configurations();
+    let fuzzer_configurations = databases.get_inputs_db().get_all_fuzzer_configurations();
 
-    for file_meta in data.get_sut_db().get_all_file_meta().values() {
+    for file_meta in databases.get_sut_db().get_all_file_meta().values() {
         let mut current_ui_file = UIFileInfo::new(file_meta);
         for &line_id in file_meta.lines.iter() {
-            current_ui_file
-                .lines
-                .push(data.get_sut_db().get_line_meta(line_id).unwrap().clone());
+            current_ui_file.lines.push(
+                databases
+                    .get_sut_db()
+                    .get_line_meta(line_id)
+                    .unwrap()
+                    .clone(),
+            );
         }
 
         // sorting is really important for the UI - otherwise the file explorer will go bonkers!
         current_ui_file.lines.sort_by_key(|l| l.line_num);
 
-        current_ui_file.content = data.get_sut_db().read_file_content(&file_meta.name);
+        current_ui_file.content = databases.get_sut_db().read_file_content(&file_meta.name);
         // removing the final '\n' which was added extra in the above iteration
         current_ui_file.content.pop();
         current_ui_file.unique_lines_covered = file_meta.unique_line_hits.clone();
@@ -233,11 +302,73 @@ pub async fn get_sut(data: web::Data<AppState>) -> impl Responder {
     serde_json::to_string(&response)
 }
 
+pub async fn get_skipped_inputs(data: web::Data<AppState>) -> impl Responder {
+    log::info!("GET /skipped_inputs");
+    let databases = data.read();
+    serde_json::to_string(databases.get_inputs_db().get_skipped_inputs())
+}
+
+#[derive(Clone, Serialize)]
+pub struct UIIngestionReport {
+    pub fuzzer_configuration_id: u32,
+    pub fuzzer_configuration_name: String,
+    pub traces_seen: u32,
+    pub traces_empty: usize,
+    pub traces_unparsable: usize,
+    pub inputs_with_missing_parents: usize,
+    pub inputs_with_ambiguous_parents: usize,
+    pub blocks_filtered_by_path: u64,
+    pub blocks_without_line: u64,
+    pub empty_traces: Vec<String>,
+    pub skipped_inputs: Vec<SkippedInput>,
+    pub orphaned_inputs: Vec<OrphanedInput>,
+    pub ambiguous_parents: Vec<AmbiguousParent>,
+}
+
+pub async fn get_ingestion_report(data: web::Data<AppState>) -> impl Responder {
+    log::info!("GET /ingestion_report");
+    let databases = data.read();
+
+    let input_db = databases.get_inputs_db();
+    let mut response: Vec<UIIngestionReport> = Vec::new();
+    for (fuzzer_configuration_id, fuzzer_config) in input_db.get_all_fuzzer_configurations() {
+        let report = input_db
+            .get_ingestion_report(fuzzer_configuration_id)
+            .cloned()
+            .unwrap_or_default();
+        let skipped_inputs: Vec<SkippedInput> = input_db
+            .get_skipped_inputs()
+            .iter()
+            .filter(|s| s.fuzzer_configuration_id == *fuzzer_configuration_id)
+            .cloned()
+            .collect();
+        response.push(UIIngestionReport {
+            fuzzer_configuration_id: *fuzzer_configuration_id,
+            fuzzer_configuration_name: fuzzer_config.fuzzer_configuration.clone(),
+            traces_seen: report.traces_seen,
+            traces_empty: report.empty_traces.len(),
+            traces_unparsable: skipped_inputs.len(),
+            inputs_with_missing_parents: report.orphaned_inputs.len(),
+            inputs_with_ambiguous_parents: report.ambiguous_parents.len(),
+            blocks_filtered_by_path: report.blocks_filtered_by_path,
+            blocks_without_line: report.blocks_without_line,
+            empty_traces: report.empty_traces,
+            skipped_inputs,
+            orphaned_inputs: report.orphaned_inputs,
+            ambiguous_parents: report.ambiguous_parents,
+        });
+    }
+    response.sort_by_key(|r| r.fuzzer_configuration_id);
+
+    serde_json::to_string(&response)
+}
+
 pub async fn get_sut_file_id_name_map(data: web::Data<AppState>) -> impl Responder {
-    println!("GET /sut_file_info");
+    log::info!("GET /sut_file_info");
+    let databases = data.read();
 
     let mut response: HashMap<usize, String> = HashMap::new();
-    for (file_id, file_meta) in data.get_sut_db().get_all_file_meta() {
+    for (file_id, file_meta) in databases.get_sut_db().get_all_file_meta() {
         response.insert(
             file_id.as_usize(),
             String::from(
@@ -297,20 +428,24 @@ pub async fn get_all_input_clusters(
     request: web::Json<UIInputClustersRequest>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    println!("POST /input_clusters {:?}", request);
+    log::info!("POST /input_clusters {:?}", request);
+    let databases = data.read();
     let mut response: HashMap<u32, HashMap<i64, UIInputClusters>> = HashMap::new();
-    let all_inputs = data.get_inputs_db().get_all_inputs_meta_info();
+    let all_inputs = databases.get_inputs_db().get_all_inputs_meta_info();
     let cluster_threshold = request.cluster_threshold_seconds * 60 * 1000; // minutes * seconds * milliseconds
-    println!("cluster threshold in milliseconds: {:?}", cluster_threshold);
+    log::debug!("cluster threshold in milliseconds: {:?}", cluster_threshold);
 
-    for (fuzzer_configuration_id, _) in data.get_inputs_db().get_all_fuzzer_configurations().iter()
+    for (fuzzer_configuration_id, _) in databases
+        .get_inputs_db()
+        .get_all_fuzzer_configurations()
+        .iter()
     {
         let current_cluster_map = response.entry(*fuzzer_configuration_id).or_default();
-        let initial_seeds_meta = data
+        let initial_seeds_meta = databases
             .get_inputs_db()
             .get_all_initial_seeds_meta_info(fuzzer_configuration_id);
 
-        let fuzzer_min_max_times = data
+        let fuzzer_min_max_times = databases
             .get_inputs_db()
             .get_run_times_for_fuzzer_id(fuzzer_configuration_id);
         let min_start_time: i64 = fuzzer_min_max_times.0;
@@ -343,7 +478,7 @@ pub async fn get_all_input_clusters(
                 .get_mut(&cluster_key_normalized)
                 .unwrap();
             current_cluster.total_fuzzer_coverage += input_metadata.fuzzer_coverage;
-            let parents = data
+            let parents = databases
                 .get_inputs_db()
                 .get_initial_seed_parents_for(input_id, fuzzer_configuration_id);
             for parent in &parents {
@@ -363,7 +498,7 @@ pub async fn get_all_input_clusters(
         }
     }
 
-    println!("POST /input_clusters response sent");
+    log::info!("POST /input_clusters response sent");
     serde_json::to_string(&response)
 }
 
@@ -383,30 +518,36 @@ pub async fn get_inputs_comparison(
     request: web::Json<CompareSeedsRequest>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    println!("POST /compare_inputs {:?}", request);
+    log::info!("POST /compare_inputs {:?}", request);
+    let databases = data.read();
     let mut ret: InitialSeedComparison = InitialSeedComparison {
         initial_seed_id: request.initial_seed_id,
         byte_modification_counts: HashMap::new(),
     };
-    let comparison = data
+    let comparison = match databases
         .get_inputs_db()
-        .compare_inputs(&request.fuzzer_configuration_id, &request.initial_seed_id);
+        .compare_inputs(&request.fuzzer_configuration_id, &request.initial_seed_id)
+    {
+        Ok(comparison) => comparison,
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    };
 
     let mut compressed_comparison: HashMap<usize, u32> = HashMap::new();
     let mut comparison_keys: Vec<usize> = comparison.keys().copied().collect();
     comparison_keys.sort();
-    let mut previous_byte_count: &u32 = comparison.get(&0).unwrap();
+    // an empty initial seed has no bytes to compare
+    let mut previous_byte_count: Option<&u32> = comparison.get(&0);
     for byte in comparison_keys.iter() {
-        let current_count = comparison.get(byte).unwrap();
-        if current_count != previous_byte_count {
+        let current_count = &comparison[byte];
+        if Some(current_count) != previous_byte_count {
             compressed_comparison.insert(*byte, *current_count);
-            previous_byte_count = current_count;
+            previous_byte_count = Some(current_count);
         }
     }
 
     ret.byte_modification_counts = compressed_comparison;
 
-    serde_json::to_string(&ret)
+    HttpResponse::Ok().json(ret)
 }
 
 #[derive(Debug, Deserialize)]
@@ -418,24 +559,28 @@ pub async fn get_initial_seeds_line_coverage_for_file(
     request: web::Json<InitialSeedsLineCoverageRequest>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    println!("POST /initial_seeds_line_coverage_for_file {:?}", request);
+    log::info!("POST /initial_seeds_line_coverage_for_file {:?}", request);
+    let databases = data.read();
     let mut response: HashMap<u32, HashMap<u32, Vec<LineMeta>>> = HashMap::new();
-    for (fuzzer_configuration_id, _) in data.get_inputs_db().get_all_fuzzer_configurations().iter()
+    for (fuzzer_configuration_id, _) in databases
+        .get_inputs_db()
+        .get_all_fuzzer_configurations()
+        .iter()
     {
         response.entry(*fuzzer_configuration_id).or_default();
 
-        for (_input_id, initial_seeds_meta) in data
+        for (_input_id, initial_seeds_meta) in databases
             .get_inputs_db()
             .get_all_initial_seeds_meta_info(fuzzer_configuration_id)
             .iter()
         {
-            let current_initial_seed_coverage = data
+            let current_initial_seed_coverage = databases
                 .get_inputs_db()
                 .get_initial_seed_line_coverage_for_file_id(
                     fuzzer_configuration_id,
                     &initial_seeds_meta.fuzz_input_id,
                     &FileId::new(request.file_id),
-                    data.get_sut_db(),
+                    databases.get_sut_db(),
                 );
             response.get_mut(fuzzer_configuration_id).unwrap().insert(
                 initial_seeds_meta.fuzz_input_id,
@@ -459,14 +604,15 @@ pub async fn get_line_coverage_for_file(
     request: web::Json<LineCoverageRequest>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    println!("POST /line_coverage_for_file {:?}", request);
-    let response = data
+    log::info!("POST /line_coverage_for_file {:?}", request);
+    let databases = data.read();
+    let response = databases
         .get_inputs_db()
         .get_all_children_line_coverage_for_file_id(
             &request.fuzzer_configuration_id,
             &request.initial_seed_id,
             &FileId::new(request.file_id),
-            data.get_sut_db(),
+            databases.get_sut_db(),
         )
         .get(&InputId::new(request.child_id))
         .unwrap()
@@ -474,6 +620,46 @@ pub async fn get_line_coverage_for_file(
     serde_json::to_string(&response)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct EdgeCoverageRequest {
+    pub fuzzer_configuration_id: u32,
+    pub file_id: usize,
+}
+
+pub async fn get_edge_coverage_for_file(
+    request: web::Json<EdgeCoverageRequest>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    log::info!("POST /edge_coverage_for_file {:?}", request);
+    let databases = data.read();
+    let input_db = databases.get_inputs_db();
+    if input_db
+        .get_fuzzer_configuration(&request.fuzzer_configuration_id)
+        .is_none()
+    {
+        return HttpResponse::BadRequest().body("unknown fuzzer configuration");
+    }
+
+    let mut response: HashMap<u32, Vec<EdgeCoverage>> = HashMap::new();
+    for fuzz_input_id in input_db
+        .get_all_input_ids_with_initial_seeds_for(&request.fuzzer_configuration_id)
+        .iter()
+        .filter_map(|input_id| input_db.find_input_meta_for(input_id))
+        .filter(|input_meta| input_meta.is_initial_seed)
+        .map(|input_meta| input_meta.fuzz_input_id)
+    {
+        response.insert(
+            fuzz_input_id,
+            input_db.get_initial_seed_edge_coverage_for_file_id(
+                &request.fuzzer_configuration_id,
+                &fuzz_input_id,
+                &FileId::new(request.file_id),
+            ),
+        );
+    }
+    HttpResponse::Ok().json(response)
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TimelineRequest {
     pub fuzzer_configuration_id: u32,
@@ -515,12 +701,13 @@ pub async fn get_initial_seed_timeline(
     request: web::Json<TimelineRequest>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    println!("POST /initial_seed_timeline {:?}", request);
+    log::info!("POST /initial_seed_timeline {:?}", request);
+    let databases = data.read();
     let mut response = TimelineResponse::new();
     let mut time_delta_to_substract = 0;
 
     for initial_seed_id in request.initial_seed_ids.iter() {
-        let initial_seed_info = data
+        let initial_seed_info = databases
             .get_inputs_db()
             .get_all_initial_seeds_meta_info(&request.fuzzer_configuration_id)
             .iter()
@@ -539,12 +726,12 @@ pub async fn get_initial_seed_timeline(
         });
     }
 
-    for child_input_id in data
+    for child_input_id in databases
         .get_inputs_db()
         .get_all_children_input_ids_for(&request.fuzzer_configuration_id, &request.initial_seed_ids)
         .iter()
     {
-        let current_meta = data
+        let current_meta = databases
             .get_inputs_db()
             .get_inputs_meta_info_for(child_input_id);
         assert!(current_meta.fuzzer_configuration == request.fuzzer_configuration_id);
@@ -559,7 +746,7 @@ pub async fn get_initial_seed_timeline(
         };
 
         for parent_id in current_meta.parents.iter() {
-            if data
+            if databases
                 .get_inputs_db()
                 .has_children_for(&request.fuzzer_configuration_id, parent_id)
             {
@@ -569,10 +756,12 @@ pub async fn get_initial_seed_timeline(
                 });
                 current_response_meta_data.push_str(&format!("initial seed-{:?}, ", *parent_id));
             } else {
-                let curr_parent = data
+                let curr_parent = databases
                     .get_inputs_db()
                     .get_input_id_for(&request.fuzzer_configuration_id, parent_id);
-                let curr_parent_meta = data.get_inputs_db().get_inputs_meta_info_for(curr_parent);
+                let curr_parent_meta = databases
+                    .get_inputs_db()
+                    .get_inputs_meta_info_for(curr_parent);
                 response.edges.push(TimelineEdge {
                     source: format!("seed-{:?}", curr_parent.as_usize()),
                     target: format!("seed-{:?}", child_input_id.as_usize()),
@@ -591,3 +780,269 @@ pub async fn get_initial_seed_timeline(
 
     serde_json::to_string(&response)
 }
+
+#[derive(Debug, Deserialize)]
+pub struct LcovExportRequest {
+    pub fuzzer_configuration_id: u32,
+    pub initial_seed_id: Option<u32>,
+    pub input_ids: Option<Vec<u32>>,
+}
+
+pub async fn get_lcov_export(
+    request: web::Json<LcovExportRequest>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    log::info!("POST /lcov {:?}", request);
+    let databases = data.read();
+    let input_db = databases.get_inputs_db();
+    let Some(fuzzer_config) = input_db.get_fuzzer_configuration(&request.fuzzer_configuration_id)
+    else {
+        return HttpResponse::BadRequest().body("unknown fuzzer configuration");
+    };
+    let input_ids = match lcov::select_inputs(
+        input_db,
+        &request.fuzzer_configuration_id,
+        request.initial_seed_id,
+        request.input_ids.as_deref(),
+    ) {
+        Ok(input_ids) => input_ids,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+
+    let line_hit_counts = input_db.get_line_hit_counts_for(&input_ids);
+    HttpResponse::Ok()
+        .content_type("text/plain")
+        .body(lcov::write_lcov(
+            &fuzzer_config.fuzzer_configuration,
+            &line_hit_counts,
+            databases.get_sut_db(),
+        ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LineHitCountsRequest {
+    pub fuzzer_configuration_id: u32,
+    pub input_id: u32,
+    pub file_id: usize,
+}
+
+#[derive(Serialize)]
+pub struct UILineHitCounts {
+    // false if the coverage backend only recorded which lines were executed, each of them then
+    // counts as executed once
+    pub execution_counts_recorded: bool,
+    pub line_hit_counts: BTreeMap<u32, u64>,
+}
+
+pub async fn get_line_hit_counts_for_file(
+    request: web::Json<LineHitCountsRequest>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    log::info!("POST /line_hit_counts_for_file {:?}", request);
+    let databases = data.read();
+    let input_db = databases.get_inputs_db();
+    let Some(input_id) =
+        input_db.find_input_id_for(&request.fuzzer_configuration_id, &request.input_id)
+    else {
+        return HttpResponse::BadRequest().body("unknown input");
+    };
+
+    let file_id = FileId::new(request.file_id);
+    let response = UILineHitCounts {
+        execution_counts_recorded: input_db.has_execution_counts(input_id),
+        line_hit_counts: input_db
+            .get_line_hit_counts_for(&[*input_id])
+            .into_iter()
+            .filter(|(line, _)| line.file() == file_id)
+            .map(|(line, count)| (line.num(), count))
+            .collect(),
+    };
+    HttpResponse::Ok().json(response)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HeatMapRequest {
+    pub fuzzer_configuration_id: u32,
+    pub file_id: usize,
+    pub initial_seed_id: Option<u32>,
+    pub input_ids: Option<Vec<u32>>,
+}
+
+pub async fn get_heat_map_for_file(
+    request: web::Json<HeatMapRequest>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    log::info!("POST /heat_map_for_file {:?}", request);
+    let databases = data.read();
+    let input_db = databases.get_inputs_db();
+    if input_db
+        .get_fuzzer_configuration(&request.fuzzer_configuration_id)
+        .is_none()
+    {
+        return HttpResponse::BadRequest().body("unknown fuzzer configuration");
+    }
+    let input_ids = match lcov::select_inputs(
+        input_db,
+        &request.fuzzer_configuration_id,
+        request.initial_seed_id,
+        request.input_ids.as_deref(),
+    ) {
+        Ok(input_ids) => input_ids,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+
+    let response: Vec<LineHeat> =
+        input_db.get_line_heat_for_file_id(&input_ids, &FileId::new(request.file_id));
+    HttpResponse::Ok().json(response)
+}
+
+pub async fn get_functions(data: web::Data<AppState>) -> impl Responder {
+    log::info!("GET /functions");
+    let databases = data.read();
+    let mut response: Vec<&FunctionMeta> = databases
+        .get_sut_db()
+        .get_all_functions()
+        .values()
+        .collect();
+    response.sort_by_key(|f| (f.file_id.as_usize(), f.start_line, f.id.as_usize()));
+    serde_json::to_string(&response)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FunctionCoverageRequest {
+    pub fuzzer_configuration_id: u32,
+    pub function_id: Option<usize>,
+    pub function_name: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UIFunctionCoverage<'a> {
+    pub function: &'a FunctionMeta,
+    pub coverage: FunctionCoverage,
+}
+
+pub async fn get_function_coverage(
+    request: web::Json<FunctionCoverageRequest>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    log::info!("POST /function_coverage {:?}", request);
+    let databases = data.read();
+    let input_db = databases.get_inputs_db();
+    if input_db
+        .get_fuzzer_configuration(&request.fuzzer_configuration_id)
+        .is_none()
+    {
+        return HttpResponse::BadRequest().body("unknown fuzzer configuration");
+    }
+
+    // static functions of different files may share a name
+    let mut functions: Vec<&FunctionMeta> = databases
+        .get_sut_db()
+        .get_all_functions()
+        .values()
+        .filter(|f| match (&request.function_id, &request.function_name) {
+            (Some(function_id), _) => f.id.as_usize() == *function_id,
+            (None, Some(function_name)) => f.name == *function_name,
+            (None, None) => false,
+        })
+        .collect();
+    if functions.is_empty() {
+        return HttpResponse::BadRequest().body("unknown function");
+    }
+    functions.sort_by_key(|f| f.id.as_usize());
+
+    let response: Vec<UIFunctionCoverage> = functions
+        .into_iter()
+        .map(|function| UIFunctionCoverage {
+            function,
+            coverage: input_db
+                .get_function_coverage_for(&request.fuzzer_configuration_id, &function.id),
+        })
+        .collect();
+    HttpResponse::Ok().json(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{lcov_target_config, lcov_target_traces};
+    use actix_web::{test as actix_test, App};
+    use config::{FuzzerConfig, QueueNameFormat};
+    use inputs_database::InputsDatabase;
+    use std::{env, fs};
+    use sut_database::SUT;
+
+    #[actix_web::test]
+    async fn test_compare_honggfuzz_inputs() {
+        let test_data_dir = fs::canonicalize("../test_data").unwrap();
+        let honggfuzz_dir = test_data_dir.join("honggfuzz");
+        let traces_dir = env::temp_dir().join("seedui_test_compare_honggfuzz_inputs");
+        let _ = fs::remove_dir_all(&traces_dir);
+        fs::create_dir_all(&traces_dir).unwrap();
+        // every input with the coverage of the lcov_target seed, named like `server extract` does
+        let mut file_names = Vec::new();
+        for directory in ["seeds", "hfuzz-out"] {
+            for entry in fs::read_dir(honggfuzz_dir.join(directory)).unwrap() {
+                let input_name = entry.unwrap().file_name().to_string_lossy().into_owned();
+                let file_name = traces_dir.join(format!("{}.info", input_name.replace(',', "::")));
+                fs::copy(&lcov_target_traces()[0], &file_name).unwrap();
+                file_names.push(file_name.to_string_lossy().into_owned());
+            }
+        }
+        file_names.sort();
+
+        let path = |name: &str| honggfuzz_dir.join(name).to_string_lossy().into_owned();
+        let target_config = lcov_target_config();
+        let mut input_db = InputsDatabase::new();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&target_config);
+        input_db.add_fuzzer_configuration(&FuzzerConfig {
+            fuzzer_configuration: "honggfuzz".to_string(),
+            traces_directory_path: traces_dir.to_string_lossy().into_owned(),
+            inputs_directory_path: path("hfuzz-out"),
+            fuzzer_configuration_id: 0,
+            queue_name_format: QueueNameFormat::HonggfuzzOutput {
+                log_path: path("hfuzz.log"),
+                seed_corpus_path: path("seeds"),
+            },
+        });
+        input_db
+            .add_inputs(&file_names, &target_config, &mut sut_db, 0, 2)
+            .unwrap();
+        input_db.post_process();
+        let _ = fs::remove_dir_all(&traces_dir);
+        assert!(input_db.get_skipped_inputs().is_empty());
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(AppState::new(input_db, sut_db)))
+                .configure(crate::configure_routes),
+        )
+        .await;
+        let compare = |initial_seed_id: u32| {
+            actix_test::TestRequest::post()
+                .uri("/compare_inputs")
+                .set_json(serde_json::json!({
+                    "fuzzer_configuration_id": 0,
+                    "initial_seed_id": initial_seed_id,
+                }))
+                .to_request()
+        };
+
+        // "a\n" of the seed corpus, its descendants 1, 2, 3 and 5 are "input 1\n" to "input 5\n"
+        let comparison: InitialSeedComparison =
+            actix_test::call_and_read_body_json(&app, compare(9)).await;
+        assert_eq!(comparison.initial_seed_id, 9);
+        assert_eq!(
+            comparison.byte_modification_counts,
+            HashMap::from([(6, 4), (7, 1)])
+        );
+
+        let response = actix_test::call_service(&app, compare(1)).await;
+        assert_eq!(response.status(), 400);
+        assert_eq!(
+            actix_test::read_body(response).await,
+            "unknown initial seed 1"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    fs::{self, File},
+    hash::Hasher,
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use config::{QueueNameFormat, UserConfig};
+use fnv::FnvHasher;
+use inputs_database::InputsDatabase;
+use sut_database::SUT;
+
+// Bump whenever the layout of InputsDatabase, SUT or any of their members changes
+const SNAPSHOT_VERSION: u32 = 14;
+
+pub fn snapshot_path_for(config_path: &Path) -> PathBuf {
+    let mut file_name = config_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".snapshot");
+    config_path.with_file_name(file_name)
+}
+
+// FNV and explicit byte orders, unlike DefaultHasher and the std Hash impls they stay the same
+// between Rust releases, so a snapshot is only rebuilt if its sources changed
+fn hash_bytes(bytes: &[u8], hasher: &mut FnvHasher) {
+    hasher.write(&(bytes.len() as u64).to_le_bytes());
+    hasher.write(bytes);
+}
+
+fn hash_file_state(path: &Path, hasher: &mut FnvHasher) {
+    hash_bytes(path.as_os_str().as_encoded_bytes(), hasher);
+    if let Ok(metadata) = fs::metadata(path) {
+        hasher.write(&metadata.len().to_le_bytes());
+        if let Ok(modified) = metadata.modified() {
+            if let Ok(since_epoch) = modified.duration_since(UNIX_EPOCH) {
+                hasher.write(&since_epoch.as_nanos().to_le_bytes());
+            }
+        }
+    }
+}
+
+fn hash_directory_state(path: &Path, hasher: &mut FnvHasher) {
+    let mut files: Vec<PathBuf> = match fs::read_dir(path) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+        Err(_) => Vec::new(),
+    };
+    // read_dir does not guarantee any order
+    files.sort();
+    for file in files.iter() {
+        hash_file_state(file, hasher);
+    }
+}
+
+// the source files below `path` with one of the `allowed_extensions`, any if there are none
+fn hash_source_tree_state(path: &Path, allowed_extensions: &[String], hasher: &mut FnvHasher) {
+    let mut entries: Vec<(PathBuf, bool)> = match fs::read_dir(path) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            // symlinked directories are not followed, they may form a cycle
+            .map(|e| (e.path(), e.file_type().is_ok_and(|t| t.is_dir())))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    entries.sort();
+    for (entry, is_dir) in entries.iter() {
+        // e.g. .git, which changes without the sources changing
+        let is_hidden = entry
+            .file_name()
+            .is_some_and(|name| name.as_encoded_bytes().starts_with(b"."));
+        if is_hidden {
+            continue;
+        }
+        if *is_dir {
+            hash_source_tree_state(entry, allowed_extensions, hasher);
+        } else if allowed_extensions.is_empty()
+            || entry.extension().is_some_and(|extension| {
+                allowed_extensions
+                    .iter()
+                    .any(|allowed| extension == allowed.as_str())
+            })
+        {
+            hash_file_state(entry, hasher);
+        }
+    }
+}
+
+/// Fingerprint of everything the databases are built from: the target and fuzzer configurations,
+/// the target binary, its additional modules and the name, size and modification time of every
+/// source file, trace file, input, initial seed and fuzzer statistics file.
+pub fn compute_fingerprint(config: &UserConfig) -> u64 {
+    let mut hasher = FnvHasher::default();
+    // the server settings do not change the databases
+    hash_bytes(format!("{:?}", config.target_info).as_bytes(), &mut hasher);
+    hash_bytes(format!("{:?}", config.fuzzer_infos).as_bytes(), &mut hasher);
+    for module_path in config.target_info.module_paths() {
+        hash_file_state(Path::new(module_path), &mut hasher);
+    }
+    // the lines with code and the functions are told from the sources
+    let target_info = &config.target_info;
+    for source_path in std::iter::once(&target_info.target_source_code_path)
+        .chain(&target_info.target_include_filter)
+    {
+        hash_source_tree_state(
+            Path::new(source_path),
+            &target_info.allowed_extensions,
+            &mut hasher,
+        );
+    }
+
+    for fuzzer_info in &config.fuzzer_infos {
+        hash_directory_state(Path::new(&fuzzer_info.traces_directory_path), &mut hasher);
+        // the ids and parents of libFuzzer and Honggfuzz inputs are taken from their corpus
+        hash_directory_state(Path::new(&fuzzer_info.inputs_directory_path), &mut hasher);
+
+        // discovery times and coverage of stock AFL++ and libFuzzer come from files of their own,
+        // the initial seeds of libFuzzer and Honggfuzz from the seed corpus
+        match &fuzzer_info.queue_name_format {
+            QueueNameFormat::AflPlusPlus => {
+                if let Some(output_directory) =
+                    Path::new(&fuzzer_info.inputs_directory_path).parent()
+                {
+                    hash_file_state(&output_directory.join("fuzzer_stats"), &mut hasher);
+                    hash_file_state(&output_directory.join("plot_data"), &mut hasher);
+                }
+            }
+            QueueNameFormat::HonggfuzzOutput {
+                log_path,
+                seed_corpus_path,
+            }
+            | QueueNameFormat::LibFuzzer {
+                log_path,
+                seed_corpus_path,
+            } => {
+                hash_file_state(Path::new(log_path), &mut hasher);
+                hash_directory_state(Path::new(seed_corpus_path), &mut hasher);
+            }
+            _ => {}
+        }
+    }
+
+    hasher.finish()
+}
+
+/// Loads the databases from `path` if the snapshot exists and was written for `fingerprint`.
+pub fn load(path: &Path, fingerprint: u64) -> Option<(InputsDatabase, SUT)> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+
+    let (version, stored_fingerprint): (u32, u64) = bincode::deserialize_from(&mut reader).ok()?;
+    if version != SNAPSHOT_VERSION || stored_fingerprint != fingerprint {
+        log::info!("snapshot {:?} is stale, rebuilding", path);
+        return None;
+    }
+
+    match bincode::deserialize_from(&mut reader) {
+        Ok(databases) => Some(databases),
+        Err(e) => {
+            log::warn!("unable to read snapshot {:?}: {:?}", path, e);
+            None
+        }
+    }
+}
+
+/// Writes the post-processed databases to `path` together with the fingerprint of their sources.
+pub fn store(
+    path: &Path,
+    fingerprint: u64,
+    input_db: &InputsDatabase,
+    sut_db: &SUT,
+) -> io::Result<()> {
+    // write to a temporary file first so that an interrupted write never leaves a corrupt snapshot
+    let tmp_path = path.with_extension("snapshot.tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    bincode::serialize_into(&mut writer, &(SNAPSHOT_VERSION, fingerprint))
+        .and_then(|_| bincode::serialize_into(&mut writer, &(input_db, sut_db)))
+        .map_err(io::Error::other)?;
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    fs::rename(tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let path = env::temp_dir().join("seedui_test_snapshot_round_trip.snapshot");
+        let input_db = InputsDatabase::new();
+        let sut_db = SUT::new();
+
+        store(&path, 42, &input_db, &sut_db).unwrap();
+        let loaded = load(&path, 42);
+        let stale = load(&path, 43);
+        let _ = fs::remove_file(&path);
+
+        let (loaded_input_db, loaded_sut_db) = loaded.unwrap();
+        assert!(loaded_input_db.get_all_inputs_meta_info().is_empty());
+        assert!(loaded_sut_db.get_all_files().is_empty());
+        assert!(stale.is_none());
+    }
+
+    #[test]
+    fn test_fingerprint_seed_corpus() {
+        let directory = env::temp_dir().join("seedui_test_fingerprint_seed_corpus");
+        let _ = fs::remove_dir_all(&directory);
+        for subdirectory in ["traces", "corpus", "seeds"] {
+            fs::create_dir_all(directory.join(subdirectory)).unwrap();
+        }
+        let path = |name: &str| directory.join(name).to_string_lossy().into_owned();
+        let mut config = UserConfig::new();
+        config.fuzzer_infos.push(config::FuzzerConfig {
+            fuzzer_configuration: "libfuzzer".to_string(),
+            traces_directory_path: path("traces"),
+            inputs_directory_path: path("corpus"),
+            fuzzer_configuration_id: 0,
+            queue_name_format: QueueNameFormat::LibFuzzer {
+                log_path: path("fuzz.log"),
+                seed_corpus_path: path("seeds"),
+            },
+        });
+
+        let fingerprint = compute_fingerprint(&config);
+        assert_eq!(compute_fingerprint(&config), fingerprint);
+        // a new initial seed without a new trace
+        fs::write(directory.join("seeds/seed"), "a").unwrap();
+        let with_seed = compute_fingerprint(&config);
+        assert_ne!(with_seed, fingerprint);
+        fs::write(directory.join("corpus/input"), "b").unwrap();
+        assert_ne!(compute_fingerprint(&config), with_seed);
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn test_fingerprint_sources() {
+        let directory = env::temp_dir().join("seedui_test_fingerprint_sources");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(directory.join("src/lib")).unwrap();
+        fs::create_dir_all(directory.join(".git")).unwrap();
+        fs::write(directory.join("src/main.c"), "int main() {}").unwrap();
+        let mut config = UserConfig::new();
+        config.target_info.target_source_code_path = directory.to_string_lossy().into_owned();
+        config.target_info.allowed_extensions = vec!["c".to_string(), "h".to_string()];
+
+        let fingerprint = compute_fingerprint(&config);
+        assert_eq!(compute_fingerprint(&config), fingerprint);
+        // neither files of other types nor hidden directories are sources
+        fs::write(directory.join("src/lib/notes.txt"), "a").unwrap();
+        fs::write(directory.join(".git/index.c"), "a").unwrap();
+        assert_eq!(compute_fingerprint(&config), fingerprint);
+        // an updated source file in a subdirectory
+        fs::write(directory.join("src/lib/util.h"), "#define A").unwrap();
+        let with_header = compute_fingerprint(&config);
+        assert_ne!(with_header, fingerprint);
+        fs::write(directory.join("src/lib/util.h"), "#define AB").unwrap();
+        assert_ne!(compute_fingerprint(&config), with_header);
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn test_snapshot_path() {
+        assert_eq!(
+            snapshot_path_for(Path::new("../examples/readelf.ron")),
+            PathBuf::from("../examples/readelf.ron.snapshot")
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! The target of `test_data/lcov_target` shared by the tests of the server, which holds the LCOV
+//! tracefiles of an initial seed and of one input derived from it
+
+use std::{fs, path::Path};
+
+use config::{CoverageBackend, FuzzerConfig, TargetConfig, UserConfig};
+use inputs_database::InputsDatabase;
+use sut_database::SUT;
+
+/// Target configuration of `test_data/lcov_target`
+pub fn lcov_target_config() -> TargetConfig {
+    let sources_dir = fs::canonicalize("../test_data/lcov_target/sources").unwrap();
+    TargetConfig {
+        // no debug information is read for LCOV tracefiles
+        target_path: sources_dir.to_string_lossy().into_owned(),
+        target_source_code_path: sources_dir.to_string_lossy().into_owned(),
+        coverage_backend: CoverageBackend::Lcov,
+        ..TargetConfig::new()
+    }
+}
+
+/// The trace files of `test_data/lcov_target`, the initial seed first
+pub fn lcov_target_traces() -> Vec<String> {
+    let mut file_names: Vec<String> = fs::read_dir("../test_data/lcov_target/traces")
+        .unwrap()
+        .map(|e| fs::canonicalize(e.unwrap().path()).unwrap())
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    file_names.sort();
+    file_names
+}
+
+/// Configuration of `test_data/lcov_target` with fuzzer configuration 0 reading its traces and
+/// inputs from `traces_dir`
+pub fn lcov_target_user_config(traces_dir: &Path) -> UserConfig {
+    UserConfig {
+        target_info: lcov_target_config(),
+        fuzzer_infos: vec![FuzzerConfig {
+            fuzzer_configuration: "afl++".to_string(),
+            traces_directory_path: traces_dir.to_string_lossy().into_owned(),
+            inputs_directory_path: traces_dir.to_string_lossy().into_owned(),
+            fuzzer_configuration_id: 0,
+            queue_name_format: Default::default(),
+        }],
+        ..UserConfig::new()
+    }
+}
+
+/// The inputs of `file_names` added for fuzzer configuration 0, as the server has them at startup
+pub fn lcov_target_databases(file_names: &[String]) -> (InputsDatabase, SUT) {
+    let config = lcov_target_config();
+    let mut input_db = InputsDatabase::new();
+    let mut sut_db = SUT::new();
+    sut_db.parse_config(&config);
+    input_db
+        .add_inputs(file_names, &config, &mut sut_db, 0, 1)
+        .unwrap();
+    input_db.post_process();
+    (input_db, sut_db)
+}
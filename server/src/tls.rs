@@ -0,0 +1,184 @@
+use std::{
+    error::Error,
+    fmt,
+    fs::File,
+    io::{self, BufReader},
+    sync::Arc,
+};
+
+use config::TlsConfig;
+use rustls::{
+    crypto::ring,
+    pki_types::{CertificateDer, PrivateKeyDer},
+    ServerConfig,
+};
+
+#[derive(Debug)]
+pub enum TlsError {
+    Io { path: String, source: io::Error },
+    NoCertificate(String),
+    NoPrivateKey(String),
+    Rustls(rustls::Error),
+}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TlsError::Io { path, source } => write!(f, "unable to read {}: {}", path, source),
+            TlsError::NoCertificate(path) => write!(f, "no PEM certificate in {}", path),
+            TlsError::NoPrivateKey(path) => write!(f, "no PEM private key in {}", path),
+            TlsError::Rustls(e) => write!(f, "invalid certificate or private key: {}", e),
+        }
+    }
+}
+
+impl Error for TlsError {}
+
+impl From<rustls::Error> for TlsError {
+    fn from(e: rustls::Error) -> Self {
+        TlsError::Rustls(e)
+    }
+}
+
+fn open(path: &str) -> Result<BufReader<File>, TlsError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|source| TlsError::Io {
+            path: path.to_string(),
+            source,
+        })
+}
+
+fn read_certificates(path: &str) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let certificates = rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|source| TlsError::Io {
+            path: path.to_string(),
+            source,
+        })?;
+    if certificates.is_empty() {
+        return Err(TlsError::NoCertificate(path.to_string()));
+    }
+    Ok(certificates)
+}
+
+fn read_private_key(path: &str) -> Result<PrivateKeyDer<'static>, TlsError> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .map_err(|source| TlsError::Io {
+            path: path.to_string(),
+            source,
+        })?
+        .ok_or_else(|| TlsError::NoPrivateKey(path.to_string()))
+}
+
+/// rustls configuration serving the certificate chain and private key of `tls`
+pub fn server_config(tls: &TlsConfig) -> Result<ServerConfig, TlsError> {
+    let certificates = read_certificates(&tls.certificate_path)?;
+    let private_key = read_private_key(&tls.private_key_path)?;
+    // ring explicitly, rustls cannot pick a default if another crate enables aws-lc-rs as well
+    let server_config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certificates, private_key)?;
+    Ok(server_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::AppState;
+    use actix_web::{rt, web, App, HttpServer};
+    use inputs_database::InputsDatabase;
+    use rustls::{pki_types::ServerName, ClientConfig, ClientConnection, RootCertStore};
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+    };
+    use sut_database::SUT;
+
+    fn test_tls_config() -> TlsConfig {
+        TlsConfig {
+            certificate_path: "../test_data/tls/certificate.pem".to_string(),
+            private_key_path: "../test_data/tls/key.pem".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_server_config_invalid() {
+        let mut tls = test_tls_config();
+        tls.private_key_path = "../test_data/tls/does_not_exist.pem".to_string();
+        assert!(matches!(server_config(&tls), Err(TlsError::Io { .. })));
+
+        // the certificate is not a key and the key is not a certificate
+        let tls = TlsConfig {
+            certificate_path: test_tls_config().private_key_path,
+            private_key_path: test_tls_config().certificate_path,
+        };
+        assert!(matches!(
+            server_config(&tls),
+            Err(TlsError::NoCertificate(_))
+        ));
+        let tls = TlsConfig {
+            private_key_path: test_tls_config().certificate_path,
+            ..test_tls_config()
+        };
+        assert!(matches!(
+            server_config(&tls),
+            Err(TlsError::NoPrivateKey(_))
+        ));
+    }
+
+    #[actix_web::test]
+    async fn test_serve_https() {
+        let server = HttpServer::new(|| {
+            App::new()
+                .app_data(web::Data::new(AppState::new(
+                    InputsDatabase::new(),
+                    SUT::new(),
+                )))
+                .configure(crate::configure_routes)
+        })
+        .bind_rustls_0_23(("127.0.0.1", 0), server_config(&test_tls_config()).unwrap())
+        .unwrap()
+        .workers(1);
+        let address = server.addrs()[0];
+        let server = server.run();
+        let server_handle = server.handle();
+        rt::spawn(server);
+
+        // a client that trusts only the self-signed test certificate
+        let response = rt::task::spawn_blocking(move || {
+            let mut roots = RootCertStore::empty();
+            roots.add_parsable_certificates(
+                read_certificates(&test_tls_config().certificate_path).unwrap(),
+            );
+            let client_config =
+                ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+                    .with_safe_default_protocol_versions()
+                    .unwrap()
+                    .with_root_certificates(roots)
+                    .with_no_client_auth();
+            let connection = ClientConnection::new(
+                Arc::new(client_config),
+                ServerName::try_from("localhost").unwrap(),
+            )
+            .unwrap();
+            let mut stream =
+                rustls::StreamOwned::new(connection, TcpStream::connect(address).unwrap());
+            stream
+                .write_all(
+                    b"GET /functions HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+                )
+                .unwrap();
+            let mut response = String::new();
+            let _ = stream.read_to_string(&mut response);
+            response
+        })
+        .await
+        .unwrap();
+        server_handle.stop(true).await;
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("[]"), "{}", response);
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    collections::HashMap,
+    fs::{self, read_dir},
+    io, thread,
+    time::Duration,
+};
+
+use actix_web::web;
+use config::{FuzzerConfig, UserConfig};
+use custom_types::InputId;
+
+use crate::{
+    app_state::AppState,
+    events::{CoverageTracker, Event},
+    responders,
+};
+
+/// Absolute paths of the files in a traces directory, sorted
+pub fn trace_file_names(traces_directory_path: &str) -> io::Result<Vec<String>> {
+    let mut file_names: Vec<String> = Vec::new();
+    for entry in read_dir(traces_directory_path)? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let path = entry.path();
+        if path.is_file() {
+            if let Ok(absolute_path) = fs::canonicalize(&path) {
+                file_names.push(absolute_path.to_string_lossy().into_owned());
+            }
+        }
+    }
+    // read_dir has no defined order, sorting keeps the assigned input ids stable between runs
+    file_names.sort();
+    Ok(file_names)
+}
+
+/// New traces of the fuzzer configurations, each with its size when the directory was last checked
+#[derive(Default)]
+pub struct PendingTraces {
+    sizes: HashMap<u32, HashMap<String, u64>>,
+    // sizes of the traces that could not be ingested, they are only tried again once they change
+    failed: HashMap<u32, HashMap<String, u64>>,
+}
+
+impl PendingTraces {
+    /// The `new_file_names` of a fuzzer configuration whose size did not change since the last
+    /// check: the tracer may still be writing the others. Empty files are never settled, tracers
+    /// like the drcov plugin of QEMU only write them once the target exits.
+    fn settled(
+        &mut self,
+        fuzzer_configuration_id: u32,
+        new_file_names: Vec<String>,
+    ) -> Vec<String> {
+        let sizes = self.sizes.entry(fuzzer_configuration_id).or_default();
+        let failed = self.failed.entry(fuzzer_configuration_id).or_default();
+        // removed before they settled
+        sizes.retain(|file_name, _| new_file_names.contains(file_name));
+        failed.retain(|file_name, _| new_file_names.contains(file_name));
+
+        let mut settled = Vec::new();
+        for file_name in new_file_names {
+            let Ok(metadata) = fs::metadata(&file_name) else {
+                continue;
+            };
+            let size = metadata.len();
+            if size == 0 || failed.get(&file_name) == Some(&size) {
+                sizes.remove(&file_name);
+                continue;
+            }
+            if sizes.insert(file_name.clone(), size) == Some(size) {
+                sizes.remove(&file_name);
+                settled.push(file_name);
+            }
+        }
+        settled
+    }
+
+    /// Remembers the size of the `file_names` of a fuzzer configuration that could not be ingested
+    fn failed(&mut self, fuzzer_configuration_id: u32, file_names: Vec<String>) {
+        let failed = self.failed.entry(fuzzer_configuration_id).or_default();
+        for file_name in file_names {
+            if let Ok(metadata) = fs::metadata(&file_name) {
+                failed.insert(file_name, metadata.len());
+            }
+        }
+    }
+}
+
+/// Ingests the traces written to the traces directories since they were last checked, returns
+/// the ids of the new inputs.
+///
+/// The new traces are resolved while requests keep reading the databases, they are only locked
+/// for writing to merge them and link them to their initial seeds. Traces that cannot be ingested
+/// are recorded in the skipped inputs and tried again once they change.
+pub fn ingest_new_traces(
+    config: &UserConfig,
+    app_state: &AppState,
+    pending_traces: &mut PendingTraces,
+) -> Vec<InputId> {
+    let mut new_traces: Vec<(&FuzzerConfig, Vec<String>)> = Vec::new();
+    for fuzzer_info in config.fuzzer_infos.iter() {
+        let file_names = match trace_file_names(&fuzzer_info.traces_directory_path) {
+            Ok(file_names) => file_names,
+            Err(e) => {
+                log::warn!(
+                    "unable to read the traces in {}: {}",
+                    fuzzer_info.traces_directory_path,
+                    e
+                );
+                continue;
+            }
+        };
+        let new_file_names: Vec<String> = {
+            let databases = app_state.read();
+            file_names
+                .into_iter()
+                .filter(|file_name| {
+                    !databases
+                        .get_inputs_db()
+                        .has_trace_file(fuzzer_info.fuzzer_configuration_id, file_name)
+                })
+                .collect()
+        };
+        let settled = pending_traces.settled(fuzzer_info.fuzzer_configuration_id, new_file_names);
+        if !settled.is_empty() {
+            new_traces.push((fuzzer_info, settled));
+        }
+    }
+    if new_traces.is_empty() {
+        return Vec::new();
+    }
+
+    let num_workers = thread::available_parallelism().map_or(1, |n| n.get());
+    // only the watcher changes the databases, they are the same once it merges
+    let resolved_traces: Vec<_> = {
+        let databases = app_state.read();
+        new_traces
+            .iter()
+            .map(|(fuzzer_info, file_names)| {
+                // the target binary was usable at startup, it may have been rebuilt since
+                databases.get_inputs_db().resolve_inputs(
+                    file_names,
+                    &config.target_info,
+                    fuzzer_info.fuzzer_configuration_id,
+                    num_workers,
+                )
+            })
+            .collect()
+    };
+
+    let new_input_ids = app_state.write().update(|input_db, sut_db| {
+        let num_inputs = input_db.get_num_inputs();
+        for ((fuzzer_info, file_names), resolved_inputs) in new_traces.iter().zip(resolved_traces) {
+            match resolved_inputs {
+                Ok(resolved_inputs) => {
+                    input_db.merge_inputs(resolved_inputs, &config.target_info, sut_db)
+                }
+                Err(e) => input_db.skip_inputs(file_names, fuzzer_info.fuzzer_configuration_id, &e),
+            }
+        }
+        // only links the new inputs, and those that were waiting for one of them as a parent
+        input_db.post_process();
+        (num_inputs + 1..=input_db.get_num_inputs())
+            .map(InputId::new)
+            .collect()
+    });
+
+    let databases = app_state.read();
+    for (fuzzer_info, file_names) in new_traces.iter() {
+        let fuzzer_configuration_id = fuzzer_info.fuzzer_configuration_id;
+        let failed = file_names
+            .iter()
+            .filter(|file_name| {
+                !databases
+                    .get_inputs_db()
+                    .has_trace_file(fuzzer_configuration_id, file_name)
+            })
+            .cloned()
+            .collect();
+        pending_traces.failed(fuzzer_configuration_id, failed);
+    }
+    new_input_ids
+}
+
+/// Checks the traces directories for new traces every `interval` for as long as the server runs,
+/// the new inputs are sent to the clients of `/events`
+pub fn spawn(config: UserConfig, app_state: web::Data<AppState>, interval: Duration) {
+    thread::spawn(move || {
+        let mut pending_traces = PendingTraces::default();
+        let mut coverage_tracker = CoverageTracker::new(&app_state.read());
+        loop {
+            thread::sleep(interval);
+            let new_input_ids = ingest_new_traces(&config, &app_state, &mut pending_traces);
+            if new_input_ids.is_empty() {
+                continue;
+            }
+            log::info!("ingested {} new input(s)", new_input_ids.len());
+            let mut events = coverage_tracker.events_for(&app_state.read(), &new_input_ids);
+            events.push(Event::Reload(responders::reload_status(&app_state)));
+            app_state.get_events().broadcast(&events);
+            log::debug!(
+                "sent {} event(s) to {} client(s)",
+                events.len(),
+                app_state.get_events().num_clients()
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{lcov_target_databases, lcov_target_traces, lcov_target_user_config};
+    use std::env;
+
+    #[test]
+    fn test_ingest_new_traces() {
+        let traces_dir = env::temp_dir().join("seedui_test_ingest_new_traces");
+        let _ = fs::remove_dir_all(&traces_dir);
+        fs::create_dir_all(&traces_dir).unwrap();
+        let trace_names = lcov_target_traces();
+        let copy_trace = |index: usize| {
+            let from = std::path::Path::new(&trace_names[index]);
+            fs::copy(from, traces_dir.join(from.file_name().unwrap())).unwrap();
+        };
+
+        let config = lcov_target_user_config(&traces_dir);
+
+        // the initial seed is traced before the server starts
+        copy_trace(0);
+        let (input_db, sut_db) = lcov_target_databases(
+            &trace_file_names(&config.fuzzer_infos[0].traces_directory_path).unwrap(),
+        );
+        let app_state = AppState::new(input_db, sut_db);
+        let mut pending_traces = PendingTraces::default();
+        assert_eq!(
+            ingest_new_traces(&config, &app_state, &mut pending_traces),
+            vec![]
+        );
+
+        // its child while the server runs, the tracer creates the file once the target started
+        let child_trace =
+            traces_dir.join(std::path::Path::new(&trace_names[1]).file_name().unwrap());
+        fs::File::create(&child_trace).unwrap();
+        for _ in 0..3 {
+            assert_eq!(
+                ingest_new_traces(&config, &app_state, &mut pending_traces),
+                vec![]
+            );
+        }
+        assert_eq!(app_state.read().get_generation(), 0);
+
+        // and writes it when it exits, it is ingested once its size stays the same
+        copy_trace(1);
+        assert_eq!(
+            ingest_new_traces(&config, &app_state, &mut pending_traces),
+            vec![]
+        );
+        assert_eq!(app_state.read().get_generation(), 0);
+        assert_eq!(
+            ingest_new_traces(&config, &app_state, &mut pending_traces),
+            vec![InputId::new(2)]
+        );
+        assert_eq!(
+            ingest_new_traces(&config, &app_state, &mut pending_traces),
+            vec![]
+        );
+        assert_eq!(app_state.read().get_generation(), 1);
+
+        // a file that is no trace is skipped, and not tried again while it stays the same
+        let not_a_trace = traces_dir.join("README");
+        fs::write(&not_a_trace, "not a trace").unwrap();
+        for _ in 0..4 {
+            assert_eq!(
+                ingest_new_traces(&config, &app_state, &mut pending_traces),
+                vec![]
+            );
+        }
+
+        let databases = app_state.read();
+        assert_eq!(databases.get_generation(), 2);
+        let input_db = databases.get_inputs_db();
+        assert!(input_db.has_children_for(&0, &0));
+        assert_eq!(input_db.get_all_inputs_for_fuzzer_id(&0).len(), 1);
+        assert_eq!(input_db.get_skipped_inputs().len(), 1);
+        assert_eq!(
+            input_db.get_skipped_inputs()[0].file_name,
+            fs::canonicalize(&not_a_trace).unwrap().to_string_lossy()
+        );
+        assert_eq!(input_db.get_ingestion_report(&0).unwrap().traces_seen, 3);
+    }
+}
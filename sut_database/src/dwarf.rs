@@ -0,0 +1,235 @@
+//! Reads the functions and the lines with code of a target binary from its DWARF information
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+use addr2line::gimli::{self, AttributeValue, EndianSlice, RunTimeEndian, UnitRef};
+use object::{Object, ObjectSection};
+
+type Reader<'a> = EndianSlice<'a, RunTimeEndian>;
+
+/// A function with code in the target binary
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DwarfFunction {
+    pub name: String,
+    /// Path of the source file as recorded at compile time
+    pub path: PathBuf,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+/// (address, file index, line) of a row of the line program of a unit
+type LineRow = (u64, u64, u32);
+
+fn file_path(unit: UnitRef<Reader>, file_index: u64) -> Option<PathBuf> {
+    let header = unit.line_program.as_ref()?.header();
+    let file = header.file(file_index)?;
+    let mut path = PathBuf::new();
+    if let Some(comp_dir) = unit.comp_dir {
+        path.push(comp_dir.to_string_lossy().as_ref());
+    }
+    // absolute directories and file names replace what was pushed before
+    if let Some(directory) = file.directory(header) {
+        path.push(unit.attr_string(directory).ok()?.to_string_lossy().as_ref());
+    }
+    path.push(
+        unit.attr_string(file.path_name())
+            .ok()?
+            .to_string_lossy()
+            .as_ref(),
+    );
+    Some(path)
+}
+
+fn line_rows(unit: UnitRef<Reader>) -> Result<Vec<LineRow>, gimli::Error> {
+    let mut rows = Vec::new();
+    let Some(program) = unit.line_program.clone() else {
+        return Ok(rows);
+    };
+    let mut program_rows = program.rows();
+    while let Some((_, row)) = program_rows.next_row()? {
+        if let (false, Some(line)) = (row.end_sequence(), row.line()) {
+            rows.push((row.address(), row.file_index(), line.get() as u32));
+        }
+    }
+    rows.sort_unstable();
+    Ok(rows)
+}
+
+/// Looks up an attribute of a subprogram, following the declaration of out of line definitions
+/// (e.g. C++ methods) and the abstract instance of inlined functions.
+fn subprogram_attr<'a>(
+    unit: UnitRef<Reader<'a>>,
+    offset: gimli::UnitOffset,
+    name: gimli::DwAt,
+) -> Result<Option<AttributeValue<Reader<'a>>>, gimli::Error> {
+    let mut offset = offset;
+    // definitions refer to their declaration at most a few levels deep
+    for _ in 0..4 {
+        let entry = unit.entry(offset)?;
+        if let Some(value) = entry.attr_value(name)? {
+            return Ok(Some(value));
+        }
+        let origin = match entry.attr_value(gimli::DW_AT_specification)? {
+            Some(origin) => Some(origin),
+            None => entry.attr_value(gimli::DW_AT_abstract_origin)?,
+        };
+        match origin {
+            Some(AttributeValue::UnitRef(origin)) => offset = origin,
+            _ => return Ok(None),
+        }
+    }
+    Ok(None)
+}
+
+/// The path of every file of the line program of a unit by file index
+fn file_paths(unit: UnitRef<Reader>) -> HashMap<u64, PathBuf> {
+    // file indices start at 0 with DWARF 5 and at 1 before, where 0 is invalid
+    let num_files = unit
+        .line_program
+        .as_ref()
+        .map_or(0, |program| program.header().file_names().len() as u64);
+    (0..=num_files)
+        .filter_map(|file_index| Some((file_index, file_path(unit, file_index)?)))
+        .collect()
+}
+
+fn unit_functions(unit: UnitRef<Reader>) -> Result<Vec<DwarfFunction>, gimli::Error> {
+    let rows = line_rows(unit)?;
+    let paths = file_paths(unit);
+    let mut functions = Vec::new();
+
+    let mut entries = unit.entries();
+    while let Some((_, entry)) = entries.next_dfs()? {
+        if entry.tag() != gimli::DW_TAG_subprogram {
+            continue;
+        }
+        // declarations and abstract instances of inlined functions have no code of their own
+        let mut ranges: Vec<gimli::Range> = Vec::new();
+        let mut die_ranges = unit.die_ranges(entry)?;
+        while let Some(range) = die_ranges.next()? {
+            ranges.push(range);
+        }
+        if ranges.is_empty() {
+            continue;
+        }
+
+        let offset = entry.offset();
+        let name = match subprogram_attr(unit, offset, gimli::DW_AT_name)? {
+            Some(value) => unit.attr_string(value)?.to_string_lossy().into_owned(),
+            None => continue,
+        };
+        let Some(AttributeValue::FileIndex(file_index)) =
+            subprogram_attr(unit, offset, gimli::DW_AT_decl_file)?
+        else {
+            continue;
+        };
+        let Some(path) = paths.get(&file_index) else {
+            continue;
+        };
+
+        // the lines of the code of the function that are in its own file, inlined code of other
+        // files does not extend it
+        let lines: Vec<u32> = ranges
+            .iter()
+            .flat_map(|range| {
+                let first = rows.partition_point(|row| row.0 < range.begin);
+                rows[first..]
+                    .iter()
+                    .take_while(|row| row.0 < range.end)
+                    // DWARF 5 lists the primary source file twice
+                    .filter(|row| paths.get(&row.1) == Some(path))
+                    .map(|row| row.2)
+                    .filter(|line| *line > 0)
+            })
+            .collect();
+        let decl_line = match subprogram_attr(unit, offset, gimli::DW_AT_decl_line)? {
+            Some(value) => value.udata_value().map(|line| line as u32),
+            None => None,
+        };
+        let Some(start_line) = decl_line.or_else(|| lines.iter().min().copied()) else {
+            continue;
+        };
+        let end_line = lines
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or(start_line)
+            .max(start_line);
+
+        functions.push(DwarfFunction {
+            name,
+            path: path.clone(),
+            start_line,
+            end_line,
+        });
+    }
+
+    Ok(functions)
+}
+
+/// Calls `visit` with every unit of the DWARF information of `binary`
+fn for_each_unit(
+    binary: &str,
+    mut visit: impl FnMut(UnitRef<Reader>) -> Result<(), gimli::Error>,
+) -> Result<(), String> {
+    let data = fs::read(binary).map_err(|e| e.to_string())?;
+    let object = object::File::parse(&*data).map_err(|e| e.to_string())?;
+    let endian = if object.is_little_endian() {
+        RunTimeEndian::Little
+    } else {
+        RunTimeEndian::Big
+    };
+    let sections = gimli::DwarfSections::load(|id| -> Result<Cow<[u8]>, gimli::Error> {
+        Ok(match object.section_by_name(id.name()) {
+            Some(section) => section.uncompressed_data().unwrap_or(Cow::Borrowed(&[])),
+            None => Cow::Borrowed(&[]),
+        })
+    })
+    .map_err(|e| e.to_string())?;
+    let dwarf = sections.borrow(|section| EndianSlice::new(section, endian));
+
+    let mut units = dwarf.units();
+    while let Some(header) = units.next().map_err(|e| e.to_string())? {
+        let unit = dwarf.unit(header).map_err(|e| e.to_string())?;
+        visit(unit.unit_ref(&dwarf)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Reads every function with code from the DWARF information of `binary`. Functions defined in
+/// several units, e.g. static inline functions of headers, are only returned once.
+pub fn read_functions(binary: &str) -> Result<Vec<DwarfFunction>, String> {
+    let mut functions: Vec<DwarfFunction> = Vec::new();
+    for_each_unit(binary, |unit| {
+        functions.extend(unit_functions(unit)?);
+        Ok(())
+    })?;
+    functions
+        .sort_by(|a, b| (&a.path, a.start_line, &a.name).cmp(&(&b.path, b.start_line, &b.name)));
+    functions.dedup_by(|a, b| a.path == b.path && a.start_line == b.start_line && a.name == b.name);
+
+    Ok(functions)
+}
+
+/// Reads the lines that have at least one address in the line programs of `binary`, by the path
+/// of their source file as recorded at compile time. Headers contribute the lines of every unit
+/// including them.
+pub fn read_code_lines(binary: &str) -> Result<HashMap<PathBuf, HashSet<u32>>, String> {
+    let mut code_lines: HashMap<PathBuf, HashSet<u32>> = HashMap::new();
+    for_each_unit(binary, |unit| {
+        let paths = file_paths(unit);
+        for (_, file_index, line) in line_rows(unit)? {
+            // line 0 marks code that belongs to no line, e.g. generated by the compiler
+            if let (Some(path), true) = (paths.get(&file_index), line > 0) {
+                code_lines.entry(path.clone()).or_default().insert(line);
+            }
+        }
+        Ok(())
+    })?;
+    Ok(code_lines)
+}
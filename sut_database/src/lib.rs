@@ -1,17 +1,61 @@
-use std::{collections::HashMap, fmt, fs::read_to_string, path::PathBuf, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    fs::{self, read_to_string},
+    io,
+    path::PathBuf,
+    str::FromStr,
+};
 
 use config::TargetConfig;
 use custom_types::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+mod dwarf;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SUT {
     file_id_to_file_meta_map: HashMap<FileId, FileMeta>,
     filename_to_file_id_map: HashMap<String, FileId>,
     file_id_line_num_line_meta_map: HashMap<LineId, LineMeta>,
+    function_id_to_function_meta_map: HashMap<FunctionId, FunctionMeta>,
+    // the innermost function every line of a function belongs to
+    line_id_to_function_id_map: HashMap<LineId, FunctionId>,
+    // the same function may be found in several modules of the target
+    file_id_start_line_name_to_function_id_map: HashMap<(FileId, u32, String), FunctionId>,
+    // files whose lines with code are known from the line tables of the target rather than guessed
+    // from their text
+    code_line_files: HashSet<FileId>,
     allowed_folders: Vec<String>,
     allowed_extensions: Vec<String>,
 }
 
+#[derive(Debug)]
+pub enum SutError {
+    UnreadableFile { path: String, source: io::Error },
+    Dwarf { path: String, reason: String },
+}
+
+impl fmt::Display for SutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SutError::UnreadableFile { path, source } => {
+                write!(f, "unable to read source file {}: {}", path, source)
+            }
+            SutError::Dwarf { path, reason } => {
+                write!(
+                    f,
+                    "unable to read the DWARF information of {}: {}",
+                    path, reason
+                )
+            }
+        }
+    }
+}
+
+impl Error for SutError {}
+
 impl fmt::Debug for SUT {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut ret = String::from("");
@@ -35,6 +79,10 @@ impl SUT {
             filename_to_file_id_map: HashMap::new(),
             file_id_to_file_meta_map: HashMap::new(),
             file_id_line_num_line_meta_map: HashMap::new(),
+            function_id_to_function_meta_map: HashMap::new(),
+            line_id_to_function_id_map: HashMap::new(),
+            file_id_start_line_name_to_function_id_map: HashMap::new(),
+            code_line_files: HashSet::new(),
             allowed_folders: Vec::new(),
             allowed_extensions: Vec::new(),
         }
@@ -57,20 +105,23 @@ impl SUT {
             .collect();
     }
 
-    pub fn parse_file(&mut self, filename: &str) -> Option<FileId> {
+    /// Registers a source file of the target and creates its lines.
+    ///
+    /// Returns `Ok(None)` if the file is filtered out by the configuration.
+    pub fn parse_file(&mut self, filename: &str) -> Result<Option<FileId>, SutError> {
         if self.filename_to_file_id_map.contains_key(filename) {
-            return self.filename_to_file_id_map.get(filename).copied();
+            return Ok(self.filename_to_file_id_map.get(filename).copied());
         }
 
         let filepath = PathBuf::from_str(filename).unwrap();
         if filepath.is_dir() {
-            return None;
+            return Ok(None);
         }
 
         if !self.allowed_extensions.is_empty() {
-            let extension = filepath.extension().unwrap_or_default().to_str().unwrap();
+            let extension = filepath.extension().unwrap_or_default().to_string_lossy();
             if !self.allowed_extensions.contains(&extension.to_string()) {
-                return None;
+                return Ok(None);
             }
         }
 
@@ -82,6 +133,14 @@ impl SUT {
         }
 
         if allowed {
+            // read the file first, so that an unreadable file is not registered without any lines
+            let bytes = fs::read(&filepath).map_err(|source| SutError::UnreadableFile {
+                path: filepath.display().to_string(),
+                source,
+            })?;
+            // some sources are not valid UTF-8 (e.g. Latin-1 comments), the line structure is kept
+            let f = String::from_utf8_lossy(&bytes);
+
             let curr_map_len = self.filename_to_file_id_map.len() + 1;
             let file_id = self
                 .filename_to_file_id_map
@@ -93,35 +152,174 @@ impl SUT {
                 .or_insert(FileMeta::new(&filepath.display().to_string()));
 
             // The current file is inserted into the map and so we need to create line ids for this file
-            match read_to_string(&filepath) {
-                Ok(f) => {
-                    for (cur_line, cur_line_str) in f.lines().enumerate() {
-                        let first_word = cur_line_str.split_whitespace().next().unwrap_or_default();
-                        let line_num: u32 = cur_line as u32 + 1;
-                        self.file_id_line_num_line_meta_map.insert(
-                            LineId::new(*file_id, line_num),
-                            LineMeta {
-                                file_id: *file_id,
-                                line_num: line_num,
-                                hit_count: 0,
-                                fuzzer_configuration_ids: Vec::new(),
-                                is_comment: cur_line_str.is_empty()
-                                    || first_word.starts_with("/*")
-                                    || first_word.starts_with("*/")
-                                    || first_word.starts_with("//")
-                                    || (first_word.len() == 1 && first_word.starts_with("*")),
-                            },
-                        );
-                        file_meta.lines.insert(LineId::new(*file_id, line_num));
+            for (cur_line, cur_line_str) in f.lines().enumerate() {
+                let first_word = cur_line_str.split_whitespace().next().unwrap_or_default();
+                let line_num: u32 = cur_line as u32 + 1;
+                self.file_id_line_num_line_meta_map.insert(
+                    LineId::new(*file_id, line_num),
+                    LineMeta {
+                        file_id: *file_id,
+                        line_num: line_num,
+                        hit_count: 0,
+                        fuzzer_configuration_ids: Vec::new(),
+                        is_comment: cur_line_str.is_empty()
+                            || first_word.starts_with("/*")
+                            || first_word.starts_with("*/")
+                            || first_word.starts_with("//")
+                            || (first_word.len() == 1 && first_word.starts_with("*")),
+                    },
+                );
+                file_meta.lines.insert(LineId::new(*file_id, line_num));
+            }
+        }
+
+        Ok(self.filename_to_file_id_map.get(filename).copied())
+    }
+
+    /// Indexes the functions in the DWARF information of `binary` whose source file is part of
+    /// the SUT, registering these files. Returns the number of functions added.
+    pub fn parse_functions(&mut self, binary: &str) -> Result<usize, SutError> {
+        let functions = dwarf::read_functions(binary).map_err(|reason| SutError::Dwarf {
+            path: binary.to_string(),
+            reason,
+        })?;
+
+        let num_functions = self.function_id_to_function_meta_map.len();
+        for function in functions {
+            // DWARF may reference files that do not exist on this machine
+            let Ok(file_path) = fs::canonicalize(&function.path) else {
+                continue;
+            };
+            if let Some(file_id) = self.parse_file(&file_path.to_string_lossy())? {
+                self.add_function(
+                    &function.name,
+                    file_id,
+                    function.start_line,
+                    function.end_line,
+                );
+            }
+        }
+
+        Ok(self.function_id_to_function_meta_map.len() - num_functions)
+    }
+
+    /// Classifies the lines of the source files in the DWARF line tables of `binary` that are part
+    /// of the SUT, registering these files: a line is code if it has at least one address, which
+    /// replaces the guess from the text of the line. Lines found to be code in an earlier module,
+    /// e.g. of a header, stay code. Returns the number of files classified.
+    pub fn parse_code_lines(&mut self, binary: &str) -> Result<usize, SutError> {
+        let code_lines = dwarf::read_code_lines(binary).map_err(|reason| SutError::Dwarf {
+            path: binary.to_string(),
+            reason,
+        })?;
+
+        let mut num_files = 0;
+        for (path, lines) in code_lines {
+            // DWARF may reference files that do not exist on this machine
+            let Ok(file_path) = fs::canonicalize(&path) else {
+                continue;
+            };
+            let Some(file_id) = self.parse_file(&file_path.to_string_lossy())? else {
+                continue;
+            };
+            num_files += 1;
+            if self.code_line_files.insert(file_id) {
+                for line_id in self.file_id_to_file_meta_map[&file_id].lines.iter() {
+                    if let Some(line_meta) = self.file_id_line_num_line_meta_map.get_mut(line_id) {
+                        line_meta.is_comment = true;
                     }
                 }
-                Err(_) => {
-                    println!("Unable to read file: {:?}", filepath);
+            }
+            for line_num in lines {
+                // lines beyond the end of the file, e.g. of outdated sources, have no LineMeta
+                if let Some(line_meta) = self
+                    .file_id_line_num_line_meta_map
+                    .get_mut(&LineId::new(file_id, line_num))
+                {
+                    line_meta.is_comment = false;
                 }
+            }
+        }
+
+        Ok(num_files)
+    }
+
+    /// Registers a function of the file `file_id`, or returns the id of the function already
+    /// registered there under the same name and start line
+    pub fn add_function(
+        &mut self,
+        name: &str,
+        file_id: FileId,
+        start_line: u32,
+        end_line: u32,
+    ) -> FunctionId {
+        let key = (file_id, start_line, name.to_string());
+        if let Some(&function_id) = self.file_id_start_line_name_to_function_id_map.get(&key) {
+            return function_id;
+        }
+
+        let function_id = FunctionId::new(self.function_id_to_function_meta_map.len() + 1);
+        self.file_id_start_line_name_to_function_id_map
+            .insert(key, function_id);
+        self.function_id_to_function_meta_map.insert(
+            function_id,
+            FunctionMeta {
+                id: function_id,
+                name: name.to_string(),
+                file_id,
+                start_line,
+                end_line,
+                hit_count: 0,
+                fuzzer_configuration_ids: Vec::new(),
+            },
+        );
+
+        for line_num in start_line..=end_line {
+            let line_id = LineId::new(file_id, line_num);
+            if !self.file_id_line_num_line_meta_map.contains_key(&line_id) {
+                continue;
+            }
+            // nested functions, e.g. lambdas, are shorter than the function around them
+            let is_innermost = match self.line_id_to_function_id_map.get(&line_id) {
+                Some(other_id) => {
+                    let other = &self.function_id_to_function_meta_map[other_id];
+                    other.end_line - other.start_line > end_line - start_line
+                }
+                None => true,
             };
+            if is_innermost {
+                self.line_id_to_function_id_map.insert(line_id, function_id);
+            }
         }
 
-        return self.filename_to_file_id_map.get(filename).copied();
+        function_id
+    }
+
+    pub fn get_all_functions(&self) -> &HashMap<FunctionId, FunctionMeta> {
+        &self.function_id_to_function_meta_map
+    }
+
+    pub fn get_function_meta(&self, function_id: &FunctionId) -> Option<&FunctionMeta> {
+        self.function_id_to_function_meta_map.get(function_id)
+    }
+
+    /// Returns the innermost function the line belongs to, if any
+    pub fn get_function_for_line(&self, line_id: LineId) -> Option<FunctionId> {
+        self.line_id_to_function_id_map.get(&line_id).copied()
+    }
+
+    pub fn set_function_covered(&mut self, function_id: FunctionId, fuzzer_configuration_id: u32) {
+        if let Some(function_meta) = self.function_id_to_function_meta_map.get_mut(&function_id) {
+            function_meta.hit_count += 1;
+            if !function_meta
+                .fuzzer_configuration_ids
+                .contains(&fuzzer_configuration_id)
+            {
+                function_meta
+                    .fuzzer_configuration_ids
+                    .push(fuzzer_configuration_id);
+            }
+        }
     }
 
     pub fn read_file_content(&self, filepath: &str) -> String {
@@ -206,6 +404,8 @@ impl SUT {
 mod tests {
     use std::{fs, path::PathBuf};
 
+    use config::CoverageBackend;
+
     use super::*;
 
     #[test]
@@ -220,15 +420,23 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
         print!("{:?}\n", config);
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        sut_db.parse_file(&format!("{}/{}", config.target_source_code_path, "main.c"));
+        sut_db
+            .parse_file(&format!("{}/{}", config.target_source_code_path, "main.c"))
+            .unwrap();
         assert_eq!(sut_db.get_all_files().len(), 1);
-        sut_db.parse_file(&format!("{}/{}", config.target_source_code_path, "foo.c"));
+        sut_db
+            .parse_file(&format!("{}/{}", config.target_source_code_path, "foo.c"))
+            .unwrap();
         assert_eq!(sut_db.get_all_files().len(), 2);
-        sut_db.parse_file(&format!("{}/{}", config.target_source_code_path, "foo.h"));
+        sut_db
+            .parse_file(&format!("{}/{}", config.target_source_code_path, "foo.h"))
+            .unwrap();
         assert_eq!(sut_db.get_all_files().len(), 3);
         let mut line_gt: HashMap<&str, usize> = HashMap::new();
         line_gt.insert("main.c", 17);
@@ -276,21 +484,29 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec!["c".to_string(), "h".to_string()],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
         print!("{:?}\n", config);
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        sut_db.parse_file(&format!("{}/{}", config.target_source_code_path, "main.c"));
+        sut_db
+            .parse_file(&format!("{}/{}", config.target_source_code_path, "main.c"))
+            .unwrap();
         assert_eq!(sut_db.get_all_files().len(), 1);
-        sut_db.parse_file(&format!(
-            "{}/{}",
-            config.target_source_code_path, "inner/foo.c"
-        ));
+        sut_db
+            .parse_file(&format!(
+                "{}/{}",
+                config.target_source_code_path, "inner/foo.c"
+            ))
+            .unwrap();
         assert_eq!(sut_db.get_all_files().len(), 2);
-        sut_db.parse_file(&format!(
-            "{}/{}",
-            config.target_source_code_path, "inner/foo.h"
-        ));
+        sut_db
+            .parse_file(&format!(
+                "{}/{}",
+                config.target_source_code_path, "inner/foo.h"
+            ))
+            .unwrap();
         assert_eq!(sut_db.get_all_files().len(), 3);
         let mut line_gt: HashMap<&str, usize> = HashMap::new();
         line_gt.insert("main.c", 17);
@@ -324,4 +540,129 @@ mod tests {
         }
         assert_eq!(actual, line_gt);
     }
+
+    #[test]
+    fn test_function_index() {
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&TargetConfig {
+            target_source_code_path: String::from(
+                fs::canonicalize(PathBuf::from("./test_data/test1"))
+                    .unwrap()
+                    .to_str()
+                    .unwrap(),
+            ),
+            ..TargetConfig::new()
+        });
+        let file_id = sut_db
+            .parse_file(&format!(
+                "{}/foo.c",
+                fs::canonicalize("./test_data/test1").unwrap().display()
+            ))
+            .unwrap()
+            .unwrap();
+
+        let test_id = sut_db.add_function("test", file_id, 5, 20);
+        // a nested function wins over the function around it, whatever the order
+        let inner_id = sut_db.add_function("inner", file_id, 6, 13);
+        let foo_id = sut_db.add_function("foo", file_id, 3, 3);
+        assert_eq!(sut_db.add_function("test", file_id, 5, 20), test_id);
+        assert_eq!(sut_db.get_all_functions().len(), 3);
+        assert_eq!(
+            sut_db.get_function_for_line(LineId::new(file_id, 3)),
+            Some(foo_id)
+        );
+        assert_eq!(
+            sut_db.get_function_for_line(LineId::new(file_id, 7)),
+            Some(inner_id)
+        );
+        assert_eq!(
+            sut_db.get_function_for_line(LineId::new(file_id, 14)),
+            Some(test_id)
+        );
+        assert_eq!(sut_db.get_function_for_line(LineId::new(file_id, 4)), None);
+        // beyond the end of the file
+        assert_eq!(sut_db.add_function("gone", file_id, 30, 40).as_usize(), 4);
+        assert_eq!(sut_db.get_function_for_line(LineId::new(file_id, 30)), None);
+
+        sut_db.set_function_covered(test_id, 1);
+        sut_db.set_function_covered(test_id, 1);
+        sut_db.set_function_covered(test_id, 2);
+        let test_meta = sut_db.get_function_meta(&test_id).unwrap();
+        assert_eq!(test_meta.hit_count, 3);
+        assert_eq!(test_meta.fuzzer_configuration_ids, vec![1, 2]);
+    }
+
+    // test_data/test1/test is built from the sources next to it with test_data/test1/make.sh
+    #[test]
+    fn test_parse_functions() {
+        let source_code_path = fs::canonicalize("./test_data/test1").unwrap();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&TargetConfig {
+            target_source_code_path: source_code_path.display().to_string(),
+            ..TargetConfig::new()
+        });
+        let num_functions = sut_db.parse_functions("test_data/test1/test").unwrap();
+        assert_eq!(num_functions, 3);
+
+        let mut functions: Vec<(String, u32, u32)> = sut_db
+            .get_all_functions()
+            .values()
+            .map(|f| (f.name.clone(), f.start_line, f.end_line))
+            .collect();
+        functions.sort();
+        assert_eq!(
+            functions,
+            vec![
+                ("foo".to_string(), 3, 3),
+                ("main".to_string(), 4, 17),
+                ("test".to_string(), 5, 20)
+            ]
+        );
+        let main_c = sut_db
+            .get_file_id(&source_code_path.join("main.c").display().to_string())
+            .unwrap();
+        let main_id = sut_db
+            .get_function_for_line(LineId::new(main_c, 12))
+            .unwrap();
+        assert_eq!(sut_db.get_function_meta(&main_id).unwrap().name, "main");
+    }
+
+    // test_data/test1/test is built from the sources next to it with test_data/test1/make.sh
+    #[test]
+    fn test_parse_code_lines() {
+        let source_code_path = fs::canonicalize("./test_data/test1").unwrap();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&TargetConfig {
+            target_source_code_path: source_code_path.display().to_string(),
+            ..TargetConfig::new()
+        });
+        assert_eq!(sut_db.parse_code_lines("test_data/test1/test").unwrap(), 2);
+        // the header only declares functions
+        assert!(sut_db
+            .get_file_id(&source_code_path.join("foo.h").display().to_string())
+            .is_none());
+
+        let code_lines = |file_name: &str| {
+            let file_id = sut_db
+                .get_file_id(&source_code_path.join(file_name).display().to_string())
+                .unwrap();
+            let mut lines: Vec<u32> = sut_db
+                .get_all_lines(file_id)
+                .iter()
+                .filter(|line_meta| !line_meta.is_comment)
+                .map(|line_meta| line_meta.line_num)
+                .collect();
+            lines.sort();
+            lines
+        };
+        // the declaration of a and b has no code, unlike the closing brace of main
+        assert_eq!(
+            code_lines("main.c"),
+            vec![4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 17]
+        );
+        assert_eq!(
+            code_lines("foo.c"),
+            vec![3, 5, 6, 7, 8, 9, 11, 12, 14, 15, 16, 18, 19, 20]
+        );
+    }
 }
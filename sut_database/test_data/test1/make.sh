@@ -0,0 +1 @@
+gcc -ggdb -o test main.c foo.c
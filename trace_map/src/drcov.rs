@@ -1,20 +1,24 @@
 //! Modified version of the one from libAFL (https://github.com/AFLplusplus/LibAFL/blob/main/crates/libafl_targets/src/drcov.rs)
-//!     - removed writer, adapted the checks to match the drcov version 2 of current qemuafl outputs and added more utility methods
+//!     - replaced the writer, parses the module table columns from the header to read drcov
+//!       version 2 and 3 of qemuafl, DynamoRIO, Frida and Lighthouse tooling, reads text basic
+//!       block tables and added more utility methods
 
 #![allow(warnings)]
 
 use core::{fmt::Debug, num::ParseIntError, ptr};
 use std::{
+    fmt,
     fs::File,
-    io::{BufRead, BufReader, Read},
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
     path::{Path, PathBuf},
 };
 
 use rangemap::RangeMap;
+use serde::{Deserialize, Serialize};
 
 /// A basic block struct
 /// This can be used to keep track of new addresses.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DrCovBasicBlock {
     /// Start of this basic block
     pub start: u64,
@@ -49,6 +53,18 @@ pub struct DrCovBasicBlockEntry {
     mod_id: u16,
 }
 
+impl DrCovBasicBlockEntry {
+    /// Create a new [`DrCovBasicBlockEntry`] starting `start` bytes into the module `mod_id`.
+    #[must_use]
+    pub fn new(start: u32, size: u16, mod_id: u16) -> Self {
+        Self {
+            start,
+            size,
+            mod_id,
+        }
+    }
+}
+
 impl From<&[u8; 8]> for DrCovBasicBlockEntry {
     fn from(value: &[u8; 8]) -> Self {
         // # Safety
@@ -95,26 +111,71 @@ impl From<&DrCovBasicBlockEntry> for &[u8] {
 }
 
 /// An entry in the `DrCov` module list.
-#[derive(Debug, Clone)]
+///
+/// Columns missing from the module table of a file are zero, `containing_id` is the module's own
+/// `id` then.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DrCovModuleEntry {
     /// The index of this module
     pub id: u16,
+    /// The index of the module this segment belongs to (module table version 3 and later)
+    pub containing_id: u16,
     /// Base of this module
     pub base: u64,
     /// End address of this module
     pub end: u64,
     /// Entry (can be zero)
     pub entry: usize,
-    // /// Checksum (can be zero)
-    // pub checksum: usize,
-    // /// Timestamp (can be zero)
-    // pub timestamp: usize,
+    /// Offset of this segment in the module file (module table version 4 and later)
+    pub offset: u64,
+    /// Preferred base of this module (module table version 5, Windows only)
+    pub preferred_base: u64,
+    /// Checksum (can be zero)
+    pub checksum: usize,
+    /// Timestamp (can be zero)
+    pub timestamp: usize,
     /// The path of this module
     pub path: PathBuf,
 }
 
-/// Read `DrCov` (v2) files created with [`DrCovWriter`] or other tools
+/// Errors while reading a `DrCov` file
+#[derive(Debug)]
+pub enum DrCovError {
+    /// The file could not be read, or ended unexpectedly
+    Io(io::Error),
+    /// The file is not a valid `DrCov` file
+    Format(String),
+}
+
+impl From<io::Error> for DrCovError {
+    fn from(value: io::Error) -> Self {
+        DrCovError::Io(value)
+    }
+}
+
+impl fmt::Display for DrCovError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrCovError::Io(e) => write!(f, "{e}"),
+            DrCovError::Format(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for DrCovError {}
+
+/// Read `DrCov` (v2 and v3) files created with [`DrCovReader::write`], qemuafl, DynamoRIO, Frida
+/// or other tools
+#[derive(Clone, PartialEq, Eq)]
 pub struct DrCovReader {
+    /// The `DRCOV VERSION` of the file
+    pub version: u32,
+    /// The `DRCOV FLAVOR` of the file, if any
+    pub flavor: Option<String>,
+    /// The version of the module table
+    pub module_table_version: u32,
+    /// The columns of the module table, in the order of the file
+    pub module_columns: Vec<String>,
     /// The modules in this `DrCov` file
     pub module_entries: Vec<DrCovModuleEntry>,
     /// The list of basic blocks as [`DrCovBasicBlockEntry`].
@@ -125,20 +186,39 @@ pub struct DrCovReader {
 impl Debug for DrCovReader {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("DrCovReader")
+            .field("version", &self.version)
             .field("modules", &self.module_entries)
             .field("basic_blocks", &self.basic_block_entries.len())
             .finish()
     }
 }
 
+/// Module table columns understood by [`DrCovReader`], other columns are skipped
+const MODULE_COLUMNS: [&str; 11] = [
+    "id",
+    "containing_id",
+    "base",
+    "start",
+    "end",
+    "entry",
+    "offset",
+    "preferred_base",
+    "checksum",
+    "timestamp",
+    "path",
+];
+
+/// Header of the basic block table when it is written as text, e.g. by `drcov -dump_text`
+const TEXT_BB_TABLE_HEADER: &str = "module id, start, size:";
+
 fn parse_hex_to_usize(str: &str) -> Result<usize, ParseIntError> {
     // Cut off the first 0x
-    usize::from_str_radix(&str[2..], 16)
+    usize::from_str_radix(str.trim_start_matches("0x"), 16)
 }
 
 fn parse_hex_to_u64(str: &str) -> Result<u64, ParseIntError> {
     // Cut off the first 0x
-    u64::from_str_radix(&str[2..], 16)
+    u64::from_str_radix(str.trim_start_matches("0x"), 16)
 }
 
 fn parse_path(s: &str) -> PathBuf {
@@ -154,118 +234,256 @@ fn parse_path(s: &str) -> PathBuf {
     PathBuf::from(s)
 }
 
+fn read_header_line<R: BufRead>(reader: &mut R, line: &mut String) -> Result<(), DrCovError> {
+    line.clear();
+    if reader.read_line(line)? == 0 {
+        return Err(DrCovError::Io(io::ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(())
+}
+
+// "Module Table: version 2, count 3"
+fn parse_module_table_header(header: &str) -> Option<(u32, usize)> {
+    let (version, count) = header
+        .trim()
+        .strip_prefix("Module Table: version ")?
+        .split_once(", count ")?;
+    Some((version.parse().ok()?, count.parse().ok()?))
+}
+
+fn parse_module_entry(columns: &[String], line: &str) -> Result<DrCovModuleEntry, DrCovError> {
+    let err = |x: &str| {
+        DrCovError::Format(format!(
+            "Unexpected module entry while parsing {x} in header: {line}"
+        ))
+    };
+
+    let mut module = DrCovModuleEntry {
+        id: 0,
+        containing_id: 0,
+        base: 0,
+        end: 0,
+        entry: 0,
+        offset: 0,
+        preferred_base: 0,
+        checksum: 0,
+        timestamp: 0,
+        path: PathBuf::new(),
+    };
+    let mut has_containing_id = false;
+    // the path is the last column and may contain ", " itself
+    for (column, value) in columns.iter().zip(line.splitn(columns.len(), ',')) {
+        let value = value.trim();
+        match column.as_str() {
+            "id" => module.id = value.parse().map_err(|_| err(column))?,
+            "containing_id" => {
+                module.containing_id = value.parse().map_err(|_| err(column))?;
+                has_containing_id = true;
+            }
+            "base" | "start" => module.base = parse_hex_to_u64(value).map_err(|_| err(column))?,
+            "end" => module.end = parse_hex_to_u64(value).map_err(|_| err(column))?,
+            "entry" => module.entry = parse_hex_to_usize(value).map_err(|_| err(column))?,
+            "offset" => module.offset = parse_hex_to_u64(value).map_err(|_| err(column))?,
+            "preferred_base" => {
+                module.preferred_base = parse_hex_to_u64(value).map_err(|_| err(column))?
+            }
+            "checksum" => module.checksum = parse_hex_to_usize(value).map_err(|_| err(column))?,
+            "timestamp" => module.timestamp = parse_hex_to_usize(value).map_err(|_| err(column))?,
+            "path" => module.path = parse_path(value),
+            _ => {}
+        }
+    }
+    if module.path.as_os_str().is_empty() {
+        return Err(err("path"));
+    }
+    if !has_containing_id {
+        module.containing_id = module.id;
+    }
+
+    Ok(module)
+}
+
+// "module[  0]: 0x0000000000001234,  12"
+fn parse_text_basic_block(line: &str) -> Option<DrCovBasicBlockEntry> {
+    let (mod_id, rest) = line.trim().strip_prefix("module[")?.split_once("]:")?;
+    let (start, size) = rest.split_once(',')?;
+    Some(DrCovBasicBlockEntry::new(
+        u32::try_from(parse_hex_to_u64(start.trim()).ok()?).ok()?,
+        size.trim().parse().ok()?,
+        mod_id.trim().parse().ok()?,
+    ))
+}
+
 impl DrCovReader {
     /// Parse a `drcov` file to memory.
-    pub fn read<P: AsRef<Path> + ?Sized>(file: &P) -> Result<Self, String> {
-        let f = File::open(file).unwrap();
+    pub fn read<P: AsRef<Path> + ?Sized>(file: &P) -> Result<Self, DrCovError> {
+        let f = File::open(file)?;
         let mut reader = BufReader::new(f);
 
         let mut header = String::new();
-        reader.read_line(&mut header).unwrap();
+        read_header_line(&mut reader, &mut header)?;
 
-        let drcov_version = "DRCOV VERSION: 2";
-        if header.to_uppercase().trim() != drcov_version {
-            return Err(format!(
-                "No valid header. Expected {drcov_version} but got {header}"
-            ));
+        let Some(Ok(version)) = header
+            .to_uppercase()
+            .trim()
+            .strip_prefix("DRCOV VERSION: ")
+            .map(str::parse::<u32>)
+        else {
+            return Err(DrCovError::Format(format!(
+                "No valid header. Expected DRCOV VERSION: 2 or 3 but got {header}"
+            )));
+        };
+        if !(2..=3).contains(&version) {
+            return Err(DrCovError::Format(format!(
+                "Unsupported drcov version {version}"
+            )));
         }
 
-        header.clear();
-        reader.read_line(&mut header).unwrap();
+        read_header_line(&mut reader, &mut header)?;
 
         let drcov_flavor = "DRCOV FLAVOR:";
+        let mut flavor = None;
         if header.to_uppercase().starts_with(drcov_flavor) {
             // Ignore flavor line if it's not present.
-            log::info!("Got drcov flavor {drcov_flavor}");
+            log::info!("Got drcov flavor {header}");
+            flavor = Some(header[drcov_flavor.len()..].trim().to_string());
 
-            header.clear();
-            reader.read_line(&mut header).unwrap();
+            read_header_line(&mut reader, &mut header)?;
         }
 
-        let Some(Ok(module_count)) = header
-            .split("Module Table: version 2, count ")
-            .nth(1)
-            .map(|x| x.trim().parse::<usize>())
-        else {
-            return Err(format!("Expected module table but got: {header}"));
+        let Some((module_table_version, module_count)) = parse_module_table_header(&header) else {
+            return Err(DrCovError::Format(format!(
+                "Expected module table but got: {header}"
+            )));
         };
 
-        header.clear();
-        reader.read_line(&mut header).unwrap();
+        read_header_line(&mut reader, &mut header)?;
 
-        if !header.starts_with("Columns: id, base, end, entry, path") {
-            return Err(format!(
+        let Some(columns) = header.trim().strip_prefix("Columns: ") else {
+            return Err(DrCovError::Format(format!(
+                "Expected module table columns but got: {header}"
+            )));
+        };
+        let module_columns: Vec<String> =
+            columns.split(',').map(|c| c.trim().to_string()).collect();
+        let has_column = |column: &str| module_columns.iter().any(|c| c == column);
+        if !has_column("id")
+            || !(has_column("base") || has_column("start"))
+            || !has_column("end")
+            || module_columns.last().map(String::as_str) != Some("path")
+        {
+            return Err(DrCovError::Format(format!(
                 "Module table has unknown or illegal columns: {header}"
-            ));
+            )));
         }
 
         let mut modules = Vec::with_capacity(module_count);
 
         for _ in 0..module_count {
-            header.clear();
-            reader.read_line(&mut header).unwrap();
-
-            let err = |x| format!("Unexpected module entry while parsing {x} in header: {header}");
-
-            let mut split = header.split(", ");
-
-            let Some(Ok(id)) = split.next().map(str::parse) else {
-                return Err(err("id"));
-            };
-
-            let Some(Ok(base)) = split.next().map(parse_hex_to_u64) else {
-                return Err(err("base"));
-            };
-
-            let Some(Ok(end)) = split.next().map(parse_hex_to_u64) else {
-                return Err(err("end"));
-            };
-
-            let Some(Ok(entry)) = split.next().map(parse_hex_to_usize) else {
-                return Err(err("entry"));
-            };
-
-            let Some(path) = split.next().map(parse_path) else {
-                return Err(err("path"));
-            };
-
-            modules.push(DrCovModuleEntry {
-                id,
-                base,
-                end,
-                entry,
-                path,
-            });
+            read_header_line(&mut reader, &mut header)?;
+            modules.push(parse_module_entry(&module_columns, &header)?);
         }
 
-        header.clear();
-        reader.read_line(&mut header).unwrap();
+        read_header_line(&mut reader, &mut header)?;
 
         //"BB Table: {} bbs\n"
         if !header.starts_with("BB Table: ") {
-            return Err(format!("Error reading BB Table header. Got: {header}"));
+            return Err(DrCovError::Format(format!(
+                "Error reading BB Table header. Got: {header}"
+            )));
         }
         let mut bb = header.split(' ');
         let Some(Ok(bb_count)) = bb.nth(2).map(str::parse) else {
-            return Err(format!(
+            return Err(DrCovError::Format(format!(
                 "Error parsing BB Table header count. Got: {header}"
-            ));
+            )));
         };
 
         let mut basic_blocks = Vec::with_capacity(bb_count);
 
-        for _ in 0..bb_count {
-            let mut bb_entry = [0_u8; 8];
-            reader.read_exact(&mut bb_entry).unwrap();
-            basic_blocks.push((&bb_entry).into());
+        if reader
+            .fill_buf()?
+            .starts_with(TEXT_BB_TABLE_HEADER.as_bytes())
+        {
+            read_header_line(&mut reader, &mut header)?;
+            for _ in 0..bb_count {
+                read_header_line(&mut reader, &mut header)?;
+                let Some(bb_entry) = parse_text_basic_block(&header) else {
+                    return Err(DrCovError::Format(format!(
+                        "Unexpected basic block entry: {header}"
+                    )));
+                };
+                basic_blocks.push(bb_entry);
+            }
+        } else {
+            for _ in 0..bb_count {
+                let mut bb_entry = [0_u8; 8];
+                reader.read_exact(&mut bb_entry)?;
+                basic_blocks.push((&bb_entry).into());
+            }
         }
 
         Ok(DrCovReader {
+            version,
+            flavor,
+            module_table_version,
+            module_columns,
             module_entries: modules,
             basic_block_entries: basic_blocks,
         })
     }
 
+    /// Write this `drcov` file with the version, flavor and the known module table columns it was
+    /// read with, and a binary basic block table.
+    pub fn write<P: AsRef<Path> + ?Sized>(&self, file: &P) -> Result<(), DrCovError> {
+        let mut writer = BufWriter::new(File::create(file)?);
+
+        writeln!(writer, "DRCOV VERSION: {}", self.version)?;
+        if let Some(flavor) = &self.flavor {
+            writeln!(writer, "DRCOV FLAVOR: {flavor}")?;
+        }
+        writeln!(
+            writer,
+            "Module Table: version {}, count {}",
+            self.module_table_version,
+            self.module_entries.len()
+        )?;
+        let columns: Vec<&str> = self
+            .module_columns
+            .iter()
+            .map(String::as_str)
+            .filter(|c| MODULE_COLUMNS.contains(c))
+            .collect();
+        writeln!(writer, "Columns: {}", columns.join(", "))?;
+        for module in &self.module_entries {
+            let values: Vec<String> = columns
+                .iter()
+                .map(|column| match *column {
+                    "id" => format!("{:3}", module.id),
+                    "containing_id" => format!("{:3}", module.containing_id),
+                    "base" | "start" => format!("{:#018x}", module.base),
+                    "end" => format!("{:#018x}", module.end),
+                    "entry" => format!("{:#018x}", module.entry),
+                    "offset" => format!("{:#018x}", module.offset),
+                    "preferred_base" => format!("{:#018x}", module.preferred_base),
+                    "checksum" => format!("{:#010x}", module.checksum),
+                    "timestamp" => format!("{:#010x}", module.timestamp),
+                    _ => module.path.to_string_lossy().into_owned(),
+                })
+                .collect();
+            writeln!(writer, "{}", values.join(", "))?;
+        }
+
+        writeln!(writer, "BB Table: {} bbs", self.basic_block_entries.len())?;
+        for basic_block in &self.basic_block_entries {
+            let bytes: &[u8] = basic_block.into();
+            writer.write_all(bytes)?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
     /// Get a list of traversed [`DrCovBasicBlock`] nodes
     #[must_use]
     pub fn basic_blocks(&self) -> Vec<DrCovBasicBlock> {
@@ -284,14 +502,18 @@ impl DrCovReader {
         ret
     }
 
+    /// Get the traversed [`DrCovBasicBlock`] nodes of the module `id`, including those of its
+    /// other segments, i.e. the modules with `containing_id` `id`.
     pub fn basic_blocks_for_module_id(&self, id: u16) -> Vec<DrCovBasicBlock> {
         let mut ret = Vec::with_capacity(self.basic_block_entries.len());
-        if let Some(module) = self.module_by_id(id) {
+        if self.module_by_id(id).is_some() {
             for basic_block in &self.basic_block_entries {
-                if basic_block.mod_id == id {
-                    let start = module.base + u64::from(basic_block.start);
-                    // let end = start + u64::from(basic_block.size);
-                    ret.push(DrCovBasicBlock::with_size(start, basic_block.size));
+                match self.module_by_id(basic_block.mod_id) {
+                    Some(segment) if segment.containing_id == id => {
+                        let start = segment.base + u64::from(basic_block.start);
+                        ret.push(DrCovBasicBlock::with_size(start, basic_block.size));
+                    }
+                    _ => {}
                 }
             }
         } else {
@@ -365,4 +587,112 @@ mod test {
         }
         assert_eq!(module_bbs.len(), 39);
     }
+
+    fn assert_round_trip(reader: &DrCovReader, name: &str) {
+        let path = std::env::temp_dir().join(format!("seedui_test_drcov_{name}.trace"));
+        reader.write(&path).unwrap();
+        let written = DrCovReader::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&written, reader);
+    }
+
+    #[test]
+    fn test_read_qemuafl_v2() {
+        let reader = DrCovReader::read("test_data/drcov/qemuafl_v2.trace").unwrap();
+        assert_eq!(reader.version, 2);
+        assert_eq!(reader.flavor.as_deref(), Some("drcov-64"));
+        assert_eq!(reader.module_entries.len(), 2);
+        let module = reader.get_module_entry("test").unwrap();
+        assert_eq!(module.id, 0);
+        assert_eq!(module.containing_id, 0);
+        assert_eq!(module.base, 0x555555554000);
+        assert_eq!(
+            reader.basic_blocks_for_module_id(module.id),
+            vec![
+                DrCovBasicBlock::new(0x555555555139, 0x555555555145),
+                DrCovBasicBlock::new(0x555555555145, 0x55555555514c),
+                DrCovBasicBlock::new(0x55555555514c, 0x555555555151),
+            ]
+        );
+        assert_round_trip(&reader, "qemuafl_v2");
+    }
+
+    #[test]
+    fn test_read_dynamorio_v2() {
+        let reader = DrCovReader::read("test_data/drcov/dynamorio_v2.trace").unwrap();
+        assert_eq!(reader.version, 2);
+        assert_eq!(
+            reader.module_columns,
+            vec![
+                "id",
+                "base",
+                "end",
+                "entry",
+                "checksum",
+                "timestamp",
+                "path"
+            ]
+        );
+        let module = reader.get_module_entry("test").unwrap();
+        assert_eq!(module.entry, 0x555555555040);
+        assert_eq!(module.checksum, 0x12f3a);
+        assert_eq!(reader.basic_blocks_for_module_id(module.id).len(), 2);
+        assert_eq!(reader.basic_blocks().len(), 3);
+        assert_round_trip(&reader, "dynamorio_v2");
+    }
+
+    #[test]
+    fn test_read_dynamorio_v3() {
+        let reader = DrCovReader::read("test_data/drcov/dynamorio_v3.trace").unwrap();
+        assert_eq!(reader.version, 3);
+        assert_eq!(reader.module_table_version, 4);
+        // the path is the last column and may contain the separator
+        let module = reader.get_module_entry("with, comma/test").unwrap();
+        assert_eq!(module.id, 0);
+        assert_eq!(reader.module_entries[1].containing_id, 0);
+        assert_eq!(reader.module_entries[1].offset, 0x1000);
+        // blocks of the code segment belong to the module of the first segment
+        assert_eq!(
+            reader.basic_blocks_for_module_id(module.id),
+            vec![
+                DrCovBasicBlock::new(0x555555555139, 0x555555555145),
+                DrCovBasicBlock::new(0x555555555145, 0x55555555514c),
+            ]
+        );
+        assert_round_trip(&reader, "dynamorio_v3");
+    }
+
+    #[test]
+    fn test_read_text_bb_table() {
+        let reader = DrCovReader::read("test_data/drcov/dynamorio_text.trace").unwrap();
+        assert_eq!(
+            reader.basic_block_entries,
+            vec![
+                DrCovBasicBlockEntry::new(0x1139, 12, 0),
+                DrCovBasicBlockEntry::new(0x1145, 7, 0),
+            ]
+        );
+        // written back with a binary basic block table
+        assert_round_trip(&reader, "dynamorio_text");
+    }
+
+    #[test]
+    fn test_read_invalid_drcov() {
+        let path = std::env::temp_dir().join("seedui_test_drcov_invalid.trace");
+        std::fs::write(
+            &path,
+            "DRCOV VERSION: 2\nModule Table: version 2, count 1\nColumns: id, entry, path\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            DrCovReader::read(&path),
+            Err(DrCovError::Format(_))
+        ));
+        std::fs::write(&path, "DRCOV VERSION: 4\n").unwrap();
+        assert!(matches!(
+            DrCovReader::read(&path),
+            Err(DrCovError::Format(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -0,0 +1,203 @@
+//! Reader for the JSON intermediate format of `gcov --json-format`, see
+//! https://gcc.gnu.org/onlinedocs/gcc/Invoking-Gcov.html, and for the JSON report of
+//! `gcovr --json`, see https://gcovr.com/en/stable/output/json.html
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use flate2::bufread::GzDecoder;
+use serde::Deserialize;
+
+use crate::{source_coverage::SourceFileCoverage, TraceError};
+
+// gcov writes <object>.gcov.json.gz unless run with --stdout
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Deserialize)]
+struct Report {
+    // only written by gcov, the directory relative source paths were recorded in
+    #[serde(default)]
+    current_working_directory: Option<String>,
+    files: Vec<ReportFile>,
+}
+
+#[derive(Deserialize)]
+struct ReportFile {
+    file: String,
+    #[serde(default)]
+    lines: Vec<ReportLine>,
+}
+
+#[derive(Deserialize)]
+struct ReportLine {
+    line_number: u32,
+    // gcov reports negative counts for some overflowing counters
+    count: i64,
+    #[serde(default)]
+    branches: Vec<ReportBranch>,
+    // gcovr lists lines that are no code, e.g. excluded by markers
+    #[serde(default, rename = "gcovr/noncode")]
+    noncode: bool,
+}
+
+#[derive(Deserialize)]
+struct ReportBranch {
+    count: i64,
+}
+
+/// Relative paths are resolved against the working directory of gcov if the file is still there,
+/// otherwise they are left to be looked up in the source code path of the target.
+fn source_path(file: String, working_directory: Option<&str>) -> String {
+    match working_directory {
+        Some(directory) if Path::new(&file).is_relative() => {
+            let joined = Path::new(directory).join(&file);
+            if joined.exists() {
+                joined.to_string_lossy().into_owned()
+            } else {
+                file
+            }
+        }
+        _ => file,
+    }
+}
+
+/// Reads the coverage of every source file in the gcov or gcovr JSON of one input. The file may be
+/// gzip compressed and may hold several reports, as `gcov --json-format --stdout` writes one per
+/// object file.
+pub fn read_gcov_json(path: &str) -> Result<Vec<SourceFileCoverage>, TraceError> {
+    let io_error = |source| TraceError::Io {
+        path: path.to_string(),
+        source,
+    };
+    let format_error = |reason: String| TraceError::Gcov {
+        path: path.to_string(),
+        reason,
+    };
+
+    let mut reader = BufReader::new(File::open(Path::new(path)).map_err(io_error)?);
+    let is_gzip = reader
+        .fill_buf()
+        .map_err(io_error)?
+        .starts_with(&GZIP_MAGIC);
+    let reader: Box<dyn Read> = if is_gzip {
+        Box::new(BufReader::new(GzDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    };
+
+    let mut files: BTreeMap<String, SourceFileCoverage> = BTreeMap::new();
+    let mut num_reports = 0;
+    for report in serde_json::Deserializer::from_reader(reader).into_iter::<Report>() {
+        let report = report.map_err(|e| format_error(e.to_string()))?;
+        num_reports += 1;
+        for report_file in report.files {
+            let file_path = source_path(
+                report_file.file,
+                report.current_working_directory.as_deref(),
+            );
+            // headers show up in the report of every object file including them
+            let file = files
+                .entry(file_path.clone())
+                .or_insert_with(|| SourceFileCoverage {
+                    path: file_path,
+                    ..SourceFileCoverage::default()
+                });
+            for line in report_file.lines.iter().filter(|l| !l.noncode) {
+                file.line_counts
+                    .push((line.line_number, line.count.max(0) as u64));
+                file.branches.extend(
+                    line.branches
+                        .iter()
+                        .map(|b| (line.line_number, b.count.max(0) as u64)),
+                );
+            }
+        }
+    }
+    if num_reports == 0 {
+        return Err(format_error("no report".to_string()));
+    }
+
+    Ok(files.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::{fs, io::Write};
+
+    #[test]
+    fn test_read_gcov_json() {
+        let files = read_gcov_json("test_data/gcov/input_a.json").unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "/usr/include/x86_64-linux-gnu/bits/stdio2.h");
+        assert_eq!(files[0].line_counts, vec![(84, 2), (86, 2)]);
+        // the working directory of gcov does not exist here
+        assert_eq!(files[1].path, "test.c");
+        assert_eq!(files[1].line_counts.len(), 25);
+        assert!(files[1].line_counts.contains(&(6, 4)));
+        assert!(files[1].line_counts.contains(&(8, 0)));
+        assert_eq!(
+            files[1].branches,
+            vec![
+                (6, 3),
+                (6, 1),
+                (7, 0),
+                (7, 3),
+                (14, 0),
+                (14, 1),
+                (27, 1),
+                (27, 0)
+            ]
+        );
+        assert!(files[1].regions.is_empty());
+    }
+
+    #[test]
+    fn test_read_gcovr_json() {
+        let files = read_gcov_json("test_data/gcov/input_a.gcovr.json").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "test.c");
+        // line 4 is excluded by a marker
+        assert_eq!(files[0].line_counts, vec![(3, 2), (5, 1), (6, 4), (8, 0)]);
+        assert_eq!(files[0].branches, vec![(6, 3), (6, 1)]);
+    }
+
+    #[test]
+    fn test_read_gcov_json_gzip_stream() {
+        // two reports, as written by gcov --json-format --stdout for two object files
+        let report = fs::read_to_string("test_data/gcov/input_a.json").unwrap();
+        let path = std::env::temp_dir().join("seedui_test_gcov_stream.json.gz");
+        let mut encoder = GzEncoder::new(fs::File::create(&path).unwrap(), Compression::default());
+        write!(encoder, "{}\n{}", report, report).unwrap();
+        encoder.finish().unwrap();
+
+        let files = read_gcov_json(&path.to_string_lossy()).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(
+            files[0].line_counts,
+            vec![(84, 2), (86, 2), (84, 2), (86, 2)]
+        );
+        assert_eq!(files[1].line_counts.len(), 50);
+
+        fs::write(&path, "").unwrap();
+        assert!(matches!(
+            read_gcov_json(&path.to_string_lossy()),
+            Err(TraceError::Gcov { .. })
+        ));
+        fs::write(
+            &path,
+            "{\"files\": [{\"file\": \"test.c\", \"lines\": [{}]}]}",
+        )
+        .unwrap();
+        assert!(matches!(
+            read_gcov_json(&path.to_string_lossy()),
+            Err(TraceError::Gcov { .. })
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,141 @@
+//! Reader for LCOV tracefiles as written by `lcov --capture`, `geninfo` or `llvm-cov export
+//! -format=lcov`, see https://github.com/linux-test-project/lcov/blob/master/man/geninfo.1
+
+use std::fs;
+
+use crate::{source_coverage::SourceFileCoverage, TraceError};
+
+// "DA:<line>,<execution count>[,<checksum>]"
+fn parse_line_record(value: &str) -> Option<(u32, u64)> {
+    let mut fields = value.split(',');
+    let line = fields.next()?.trim().parse().ok()?;
+    // gcov reports negative counts for some overflowing counters
+    let count = fields.next()?.trim().parse::<i64>().ok()?.max(0) as u64;
+    Some((line, count))
+}
+
+// "BRDA:<line>,[<exception>]<block>,<branch>,<taken>", taken is "-" if the branch was never
+// evaluated
+fn parse_branch_record(value: &str) -> Option<(u32, u64)> {
+    let fields: Vec<&str> = value.split(',').collect();
+    let [line, .., taken] = fields.as_slice() else {
+        return None;
+    };
+    if fields.len() < 4 {
+        return None;
+    }
+    let taken = match taken.trim() {
+        "-" => 0,
+        taken => taken.parse().ok()?,
+    };
+    Some((line.trim().parse().ok()?, taken))
+}
+
+/// Reads the coverage of every source file in the LCOV tracefile of one input
+pub fn read_lcov(path: &str) -> Result<Vec<SourceFileCoverage>, TraceError> {
+    let tracefile = fs::read_to_string(path).map_err(|source| TraceError::Io {
+        path: path.to_string(),
+        source,
+    })?;
+    let format_error = |line_num: usize, reason: &str| TraceError::Lcov {
+        path: path.to_string(),
+        reason: format!("line {}: {}", line_num + 1, reason),
+    };
+
+    let mut files = Vec::new();
+    let mut current_file: Option<SourceFileCoverage> = None;
+    for (line_num, line) in tracefile.lines().enumerate() {
+        let line = line.trim();
+        if line == "end_of_record" {
+            let Some(file) = current_file.take() else {
+                return Err(format_error(line_num, "end_of_record without SF"));
+            };
+            files.push(file);
+            continue;
+        }
+        let Some((record, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        match record {
+            "SF" => {
+                if current_file.is_some() {
+                    return Err(format_error(line_num, "SF without end_of_record"));
+                }
+                current_file = Some(SourceFileCoverage {
+                    path: value.to_string(),
+                    ..SourceFileCoverage::default()
+                });
+            }
+            "DA" | "BRDA" => {
+                let Some(file) = current_file.as_mut() else {
+                    return Err(format_error(line_num, "record outside of SF"));
+                };
+                if record == "DA" {
+                    let line_count = parse_line_record(value)
+                        .ok_or_else(|| format_error(line_num, "invalid DA record"))?;
+                    file.line_counts.push(line_count);
+                } else {
+                    let branch = parse_branch_record(value)
+                        .ok_or_else(|| format_error(line_num, "invalid BRDA record"))?;
+                    file.branches.push(branch);
+                }
+            }
+            // test names, function and summary records
+            _ => {}
+        }
+    }
+    if current_file.is_some() {
+        return Err(format_error(
+            tracefile.lines().count(),
+            "missing end_of_record",
+        ));
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_lcov() {
+        let files = read_lcov("test_data/lcov/input_a.info").unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "test.c");
+        assert_eq!(files[0].line_counts.len(), 32);
+        assert!(files[0].line_counts.contains(&(6, 4)));
+        assert!(files[0].line_counts.contains(&(8, 0)));
+        assert_eq!(
+            files[0].branches,
+            vec![
+                (6, 3),
+                (6, 1),
+                (7, 0),
+                (7, 3),
+                (14, 0),
+                (14, 1),
+                (27, 1),
+                (27, 0)
+            ]
+        );
+        assert_eq!(files[1].line_counts, vec![(84, 2), (85, 2)]);
+    }
+
+    #[test]
+    fn test_read_lcov_invalid() {
+        let path = std::env::temp_dir().join("seedui_test_lcov_invalid.info");
+        std::fs::write(&path, "SF:test.c\nDA:3,2\n").unwrap();
+        assert!(matches!(
+            read_lcov(&path.to_string_lossy()),
+            Err(TraceError::Lcov { .. })
+        ));
+        std::fs::write(&path, "SF:test.c\nDA:three,2\nend_of_record\n").unwrap();
+        assert!(matches!(
+            read_lcov(&path.to_string_lossy()),
+            Err(TraceError::Lcov { .. })
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,31 +1,103 @@
 use std::{
-    collections::HashSet,
-    fs,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs, io,
     path::{Path, PathBuf},
+    sync::Mutex,
 };
 use std::fmt;
 
-use config::TargetConfig;
-use custom_types::{FileId, LineId};
-use sut_database::SUT;
+use config::{CoverageBackend, TargetConfig};
+use custom_types::{EdgeId, FileId, LineId};
+use serde::{Deserialize, Serialize};
+use sut_database::{SutError, SUT};
 
 mod trace_loader;
-use trace_loader::TraceLoader;
+use trace_loader::{SourceLocation, TraceLoader};
 
 mod drcov;
+pub use drcov::DrCovError;
 use drcov::{DrCovReader, DrCovBasicBlock};
 
-#[derive(Eq, PartialEq, Hash, Clone)]
+mod gcov;
+mod lcov;
+mod llvm_cov;
+mod sancov;
+mod source_coverage;
+use source_coverage::SourceFileCoverage;
+
+#[derive(Debug)]
+pub enum TraceError {
+    Loader { binary: String, reason: String },
+    Io { path: String, source: io::Error },
+    DrCov { path: String, source: DrCovError },
+    LlvmCov { path: String, reason: String },
+    Lcov { path: String, reason: String },
+    Gcov { path: String, reason: String },
+    Sancov { path: String, reason: String },
+    ModuleNotFound { path: String, module: String },
+    Sut(SutError),
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TraceError::Loader { binary, reason } => {
+                write!(
+                    f,
+                    "unable to load debug information of {}: {}",
+                    binary, reason
+                )
+            }
+            TraceError::Io { path, source } => write!(f, "{}: {}", path, source),
+            TraceError::DrCov { path, source } => {
+                write!(f, "invalid drcov file {}: {}", path, source)
+            }
+            TraceError::LlvmCov { path, reason } => {
+                write!(f, "invalid llvm-cov export {}: {}", path, reason)
+            }
+            TraceError::Lcov { path, reason } => {
+                write!(f, "invalid LCOV tracefile {}: {}", path, reason)
+            }
+            TraceError::Gcov { path, reason } => {
+                write!(f, "invalid gcov JSON file {}: {}", path, reason)
+            }
+            TraceError::Sancov { path, reason } => {
+                write!(f, "invalid sancov file {}: {}", path, reason)
+            }
+            TraceError::ModuleNotFound { path, module } => {
+                write!(f, "module {} not found in {}", module, path)
+            }
+            TraceError::Sut(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for TraceError {}
+
+impl From<SutError> for TraceError {
+    fn from(value: SutError) -> Self {
+        TraceError::Sut(value)
+    }
+}
+
+#[derive(Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
 pub struct SrcCovBasicBlock {
     pub start: LineId,
     pub end: LineId,
 }
 
-#[derive(Eq, PartialEq, Clone)]
+#[derive(Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Trace {
     binary: Vec<DrCovBasicBlock>,
     pub source: Vec<SrcCovBasicBlock>,
     pub unique_lines_set: HashSet<LineId>,
+    // execution count of every line in unique_lines_set, only known for backends that count and
+    // for drcov traces in full trace mode
+    pub line_hit_counts: HashMap<LineId, u64>,
+    // transfers between the lines of basic blocks that follow each other in the trace, only known
+    // for drcov traces in full trace mode, which list the blocks in the order they were executed
+    pub edges: HashSet<EdgeId>,
 }
 
 impl fmt::Debug for SrcCovBasicBlock {
@@ -55,8 +127,149 @@ impl fmt::Debug for Trace {
         write!(f, "{}", ret)
     }
 }
+/// Maps drcov traces of one target binary and its shared objects onto source lines of the `SUT`.
+///
+/// A `TraceMap` is meant to be created once per target binary and shared by all inputs of all
+/// fuzzer configurations: addresses are symbolized only once and every basic block is mapped to
+/// its `SrcCovBasicBlock` only once, no matter in how many traces it appears.
 pub struct TraceMap {
-    loader: TraceLoader,
+    // one per module of the target in the order of `TargetConfig::module_paths`, only loaded for
+    // backends that record addresses
+    loaders: Vec<TraceLoader>,
+    // (module index, start, end) relative to the module base -> how the block maps onto the
+    // source. The FileIds in here belong to the SUT the traces are mapped onto, so a TraceMap must
+    // only be used with a single SUT database.
+    block_table: Mutex<HashMap<(usize, u64, u64), BlockMapping>>,
+    // PC relative to the base of the target binary -> the line it maps onto, for backends
+    // recording single PCs
+    pc_table: Mutex<HashMap<u64, BlockMapping>>,
+    // source file path recorded by a source based backend -> its file in the SUT, if any
+    file_table: Mutex<HashMap<String, Option<FileId>>>,
+}
+
+#[derive(Clone)]
+enum BlockMapping {
+    Mapped(SrcCovBasicBlock),
+    // start or end address has no DWARF line
+    WithoutLine,
+    // the source file is missing, excluded by the SUT or outside of the configured source paths
+    FilteredByPath,
+    // start and end are on the same line, which is None if outside of the configured source paths
+    SingleLine(Option<LineId>),
+}
+
+impl BlockMapping {
+    /// The first and last line of the block, if it maps onto the source
+    fn lines(&self) -> Option<(LineId, LineId)> {
+        match self {
+            BlockMapping::Mapped(src_block) => Some((src_block.start, src_block.end)),
+            BlockMapping::SingleLine(Some(line)) => Some((*line, *line)),
+            _ => None,
+        }
+    }
+}
+
+/// Adds one execution of every line of each block to `counts`
+fn count_executions(blocks: &[Option<(LineId, LineId)>], counts: &mut HashMap<LineId, u64>) {
+    for (start, end) in blocks.iter().flatten() {
+        for line in start.num()..=end.num() {
+            *counts.entry(LineId::new(start.file(), line)).or_default() += 1;
+        }
+    }
+}
+
+/// Collects the edges between the lines of consecutive basic blocks. A block that does not map
+/// onto the source, e.g. one of a library, hides where control went, so no edge spans it. Edges
+/// between files are calls and returns rather than branches and are left out.
+fn consecutive_edges(
+    blocks: impl IntoIterator<Item = Option<(LineId, LineId)>>,
+) -> HashSet<EdgeId> {
+    let mut edges = HashSet::new();
+    let mut previous: Option<(LineId, LineId)> = None;
+    for lines in blocks {
+        if let (Some((_, from)), Some((to, _))) = (previous, lines) {
+            if from.file() == to.file() && from != to {
+                edges.insert(EdgeId::new(from, to));
+            }
+        }
+        previous = lines;
+    }
+    edges
+}
+
+/// Number of basic blocks of a trace that did not make it into its `Trace`, by reason. For source
+/// based backends, the executed code regions count as blocks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MappingStats {
+    pub blocks_filtered_by_path: u64,
+    pub blocks_without_line: u64,
+}
+
+/// The basic blocks of the target modules in a drcov trace or the PCs of a sancov file, with all
+/// their addresses already symbolized by the `TraceMap`. Resolving is the expensive part and does
+/// not need access to the `SUT`, so it can be done in parallel; `TraceMap::map_trace` has to be
+/// called in a deterministic order.
+#[derive(Clone, Debug, Default)]
+pub struct ResolvedTrace {
+    modules: Vec<ModuleBlocks>,
+    // relative to the module base
+    pcs: Vec<u64>,
+    source_files: Vec<SourceFileCoverage>,
+    empty_file: bool,
+}
+
+/// The basic blocks of one module of the target in a drcov trace
+#[derive(Clone, Debug)]
+struct ModuleBlocks {
+    // index into `TargetConfig::module_paths`
+    module: usize,
+    base: u64,
+    // where the module expected to be loaded, only recorded by some drcov versions. For non PIE
+    // binaries, this is where the virtual addresses of the DWARF information start.
+    preferred_base: u64,
+    blocks: Vec<DrCovBasicBlock>,
+}
+
+impl ModuleBlocks {
+    /// The virtual address of `address` in the module file, as used by its DWARF information
+    fn relative(&self, address: u64) -> u64 {
+        address - self.base + self.preferred_base
+    }
+}
+
+impl ResolvedTrace {
+    /// Whether the drcov file itself was empty, e.g. because the tracer crashed.
+    pub fn is_empty_file(&self) -> bool {
+        self.empty_file
+    }
+}
+
+/// The basic blocks of the modules of the target in a drcov trace, in the order of the trace
+fn module_blocks(
+    reader: &DrCovReader,
+    trace_file: &str,
+    target_config: &TargetConfig,
+) -> Result<Vec<ModuleBlocks>, TraceError> {
+    let mut modules = Vec::new();
+    for (module, module_path) in target_config.module_paths().enumerate() {
+        let Some(entry) = reader.get_module_entry(module_path) else {
+            // shared objects may be loaded late or not at all, the target binary is always there
+            if module == 0 {
+                return Err(TraceError::ModuleNotFound {
+                    path: trace_file.to_string(),
+                    module: module_path.to_string(),
+                });
+            }
+            continue;
+        };
+        modules.push(ModuleBlocks {
+            module,
+            base: entry.base,
+            preferred_base: entry.preferred_base,
+            blocks: reader.basic_blocks_for_module_id(entry.id),
+        });
+    }
+    Ok(modules)
 }
 
 fn check_ancestors(filepath: &Path, target_config: &TargetConfig) -> bool {
@@ -77,121 +290,389 @@ fn check_ancestors(filepath: &Path, target_config: &TargetConfig) -> bool {
     false
 }
 
+fn map_file(location: &SourceLocation, sut_db: &mut SUT) -> Result<Option<FileId>, SutError> {
+    // DWARF may reference files that do not exist on this machine, these can never be in the SUT
+    let Ok(file_path) = fs::canonicalize(PathBuf::from(&*location.file)) else {
+        return Ok(None);
+    };
+    sut_db.parse_file(&file_path.to_string_lossy())
+}
+
 impl TraceMap {
-    pub fn new(binary: &str) -> TraceMap {
-        let binary_path = PathBuf::from(binary);
-        TraceMap {
-            loader: TraceLoader::new(&binary_path.display().to_string()),
+    pub fn new(binary: &str) -> Result<TraceMap, TraceError> {
+        TraceMap::with_modules(std::iter::once(binary))
+    }
+
+    /// Creates a `TraceMap` for the target binary and shared objects in `module_paths`, the target
+    /// binary first.
+    pub fn with_modules<'a>(
+        module_paths: impl Iterator<Item = &'a str>,
+    ) -> Result<TraceMap, TraceError> {
+        let loaders = module_paths
+            .map(|module_path| {
+                TraceLoader::new(&PathBuf::from(module_path).display().to_string()).map_err(|e| {
+                    TraceError::Loader {
+                        binary: module_path.to_string(),
+                        reason: e.to_string(),
+                    }
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(TraceMap {
+            loaders,
+            block_table: Mutex::new(HashMap::new()),
+            pc_table: Mutex::new(HashMap::new()),
+            file_table: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Creates the `TraceMap` for the coverage backend of `target_config`. The DWARF information of
+    /// the target is only loaded if the backend records addresses.
+    pub fn for_target(target_config: &TargetConfig) -> Result<TraceMap, TraceError> {
+        match target_config.coverage_backend {
+            CoverageBackend::DrCov | CoverageBackend::DrCovFullTrace | CoverageBackend::Sancov => {
+                TraceMap::with_modules(target_config.module_paths())
+            }
+            CoverageBackend::LlvmCovJson | CoverageBackend::Lcov | CoverageBackend::GcovJson => {
+                Ok(TraceMap {
+                    loaders: Vec::new(),
+                    block_table: Mutex::new(HashMap::new()),
+                    pc_table: Mutex::new(HashMap::new()),
+                    file_table: Mutex::new(HashMap::new()),
+                })
+            }
         }
     }
 
-    pub fn parse_with_config(
-        self,
+    /// Reads the coverage recorded in `trace_file`. For drcov traces, these are the basic blocks of
+    /// the target module with their addresses symbolized, for sancov files the PCs.
+    pub fn resolve(
+        &self,
         trace_file: &str,
         target_config: &TargetConfig,
-        sut_db: &mut SUT,
-    ) -> Trace {
-        let mut current_filtered_trace = Trace {
-            binary: Vec::new(),
-            source: Vec::new(),
-            unique_lines_set: HashSet::new(),
-        };
+    ) -> Result<ResolvedTrace, TraceError> {
+        let mut resolved_trace = ResolvedTrace::default();
 
         // When afl-qemu-trace crashes in between, there may be some empty drcov trace files
         let file_size = std::fs::metadata(trace_file)
-            .expect("file metadata not found")
+            .map_err(|source| TraceError::Io {
+                path: trace_file.to_string(),
+                source,
+            })?
             .len();
         if file_size == 0 {
-            return current_filtered_trace;
+            resolved_trace.empty_file = true;
+            return Ok(resolved_trace);
         }
 
-        let reader = DrCovReader::read(&trace_file).unwrap();
-        let (base, mod_id) = match reader.get_module_entry(&target_config.target_path) {
-            Some(some_entry) => (some_entry.base, some_entry.id),
-            None => {
-                panic!("Module {} not found!", trace_file);
+        match target_config.coverage_backend {
+            CoverageBackend::DrCov | CoverageBackend::DrCovFullTrace | CoverageBackend::Sancov => {}
+            CoverageBackend::LlvmCovJson => {
+                resolved_trace.source_files = llvm_cov::read_llvm_cov_export(trace_file)?;
+                return Ok(resolved_trace);
+            }
+            CoverageBackend::Lcov => {
+                resolved_trace.source_files = lcov::read_lcov(trace_file)?;
+                return Ok(resolved_trace);
+            }
+            CoverageBackend::GcovJson => {
+                resolved_trace.source_files = gcov::read_gcov_json(trace_file)?;
+                return Ok(resolved_trace);
             }
+        }
+
+        let not_loaded = |module_path: &str| TraceError::Loader {
+            binary: module_path.to_string(),
+            reason: "not loaded for this coverage backend".to_string(),
         };
+        if target_config.coverage_backend == CoverageBackend::Sancov {
+            // the sancov file of an input only holds the PCs of the target binary
+            let loader = self
+                .loaders
+                .first()
+                .ok_or_else(|| not_loaded(&target_config.target_path))?;
+            resolved_trace.pcs = sancov::read_sancov(trace_file)?;
+            for pc in resolved_trace.pcs.iter() {
+                loader.get_location(*pc);
+            }
+            return Ok(resolved_trace);
+        }
+
+        let reader = DrCovReader::read(&trace_file).map_err(|source| TraceError::DrCov {
+            path: trace_file.to_string(),
+            source,
+        })?;
+        resolved_trace.modules = module_blocks(&reader, trace_file, target_config)?;
+        for module_blocks in resolved_trace.modules.iter() {
+            let loader = self.loaders.get(module_blocks.module).ok_or_else(|| {
+                not_loaded(
+                    target_config
+                        .module_paths()
+                        .nth(module_blocks.module)
+                        .unwrap_or_default(),
+                )
+            })?;
+            for bb in module_blocks.blocks.iter() {
+                // the blocks hold the addresses the module was loaded at
+                loader.get_location(module_blocks.relative(bb.start));
+                loader.get_location(module_blocks.relative(bb.end));
+            }
+        }
 
-        let binary_traces = reader.basic_blocks_for_module_id(mod_id);
+        Ok(resolved_trace)
+    }
+
+    fn map_basic_block(
+        &self,
+        block: (usize, u64, u64),
+        target_config: &TargetConfig,
+        sut_db: &mut SUT,
+    ) -> Result<BlockMapping, SutError> {
+        let Some(loader) = self.loaders.get(block.0) else {
+            return Ok(BlockMapping::WithoutLine);
+        };
+        let source_loc_find = loader.get_location(block.1);
+        let end_loc_find = loader.get_location(block.2);
+        let source_file = match &source_loc_find {
+            Some(l) => map_file(l, sut_db)?,
+            None => None,
+        };
+        let end_file = match &end_loc_find {
+            Some(l) => map_file(l, sut_db)?,
+            None => None,
+        };
+
+        let (Some(source_loc_find), Some(end_loc_find)) = (source_loc_find, end_loc_find) else {
+            return Ok(BlockMapping::WithoutLine);
+        };
+        let (Some(source_line), Some(end_line)) = (source_loc_find.line, end_loc_find.line) else {
+            return Ok(BlockMapping::WithoutLine);
+        };
+        let (Some(source_file), Some(end_file)) = (source_file, end_file) else {
+            return Ok(BlockMapping::FilteredByPath);
+        };
+
+        let source_loc = LineId::new(source_file, source_line);
+        let end_loc = LineId::new(end_file, end_line);
+        let in_source_paths = check_ancestors(Path::new(&*source_loc_find.file), target_config)
+            && check_ancestors(Path::new(&*end_loc_find.file), target_config);
+        if source_loc == end_loc {
+            Ok(BlockMapping::SingleLine(
+                in_source_paths.then_some(source_loc),
+            ))
+        } else if in_source_paths {
+            Ok(BlockMapping::Mapped(SrcCovBasicBlock {
+                start: source_loc,
+                end: end_loc,
+            }))
+        } else {
+            Ok(BlockMapping::FilteredByPath)
+        }
+    }
+
+    fn map_pc(
+        &self,
+        pc: u64,
+        target_config: &TargetConfig,
+        sut_db: &mut SUT,
+    ) -> Result<BlockMapping, SutError> {
+        let Some(location) = self.loaders.first().and_then(|l| l.get_location(pc)) else {
+            return Ok(BlockMapping::WithoutLine);
+        };
+        let Some(line) = location.line else {
+            return Ok(BlockMapping::WithoutLine);
+        };
+        match map_file(&location, sut_db)? {
+            Some(file_id) if check_ancestors(Path::new(&*location.file), target_config) => {
+                let line_id = LineId::new(file_id, line);
+                Ok(BlockMapping::Mapped(SrcCovBasicBlock {
+                    start: line_id,
+                    end: line_id,
+                }))
+            }
+            _ => Ok(BlockMapping::FilteredByPath),
+        }
+    }
+
+    fn map_source_file(
+        &self,
+        path: &str,
+        target_config: &TargetConfig,
+        sut_db: &mut SUT,
+    ) -> Result<Option<FileId>, SutError> {
+        let mut file_table = self.file_table.lock().unwrap();
+        if let Some(file_id) = file_table.get(path) {
+            return Ok(*file_id);
+        }
+
+        // paths relative to the compilation directory are looked up in the source code directory
+        let source_path = Path::new(&target_config.target_source_code_path).join(path);
+        let file_id = match fs::canonicalize(source_path) {
+            Ok(file_path) if check_ancestors(&file_path, target_config) => {
+                sut_db.parse_file(&file_path.to_string_lossy())?
+            }
+            _ => None,
+        };
+        file_table.insert(path.to_string(), file_id);
+        Ok(file_id)
+    }
+
+    /// Maps a resolved trace onto the lines of `sut_db`, registering newly seen source files.
+    /// Also returns how many of its blocks were dropped and why.
+    pub fn map_trace(
+        &self,
+        resolved_trace: ResolvedTrace,
+        target_config: &TargetConfig,
+        sut_db: &mut SUT,
+    ) -> Result<(Trace, MappingStats), TraceError> {
+        let mut current_filtered_trace = Trace {
+            binary: Vec::new(),
+            source: Vec::new(),
+            unique_lines_set: HashSet::new(),
+            line_hit_counts: HashMap::new(),
+            edges: HashSet::new(),
+        };
+        let mut mapping_stats = MappingStats::default();
         let mut source_trace_set: HashSet<SrcCovBasicBlock> = HashSet::new();
+        let mut block_table = self.block_table.lock().unwrap();
 
-        for bb in binary_traces {
-            // the substraction with base is necessary as the start and base are the virtual addresses
-            let soure_loc_find = self.loader.get_location(bb.start - base);
-            let (source_loc, source_found) = match &soure_loc_find {
-                Some(e) => match sut_db.parse_file(
-                    fs::canonicalize(PathBuf::from(e.file.unwrap()))
-                        .unwrap()
-                        .to_str()
-                        .unwrap(),
-                ) {
-                    Some(e_f) => {
-                        let mut ret = (LineId::new(FileId::new(usize::MAX), 0), false);
-                        if e.line.is_some() {
-                            ret = (LineId::new(e_f, e.line.unwrap()), true);
-                        }
-                        ret
-                    }
+        // only a full trace lists a block again every time it is executed, in the order of
+        // execution. A plain drcov trace lists a block once, in the order it was first
+        // translated, or again if it was translated again, e.g. after the translation cache was
+        // flushed, so it tells nothing about execution counts or edges.
+        let is_full_trace = target_config.coverage_backend == CoverageBackend::DrCovFullTrace;
+        let mut block_execution_counts: HashMap<LineId, u64> = HashMap::new();
+        for module_blocks in resolved_trace.modules.iter() {
+            let mut block_lines = Vec::with_capacity(module_blocks.blocks.len());
+            for bb in module_blocks.blocks.iter() {
+                let block = (
+                    module_blocks.module,
+                    module_blocks.relative(bb.start),
+                    module_blocks.relative(bb.end),
+                );
+                let src_block = match block_table.get(&block) {
+                    Some(src_block) => src_block.clone(),
                     None => {
-                        (LineId::new(FileId::new(usize::MAX), 0), false)
+                        let src_block = self.map_basic_block(block, target_config, sut_db)?;
+                        block_table.insert(block, src_block.clone());
+                        src_block
                     }
-                },
-                None => {
-                    (LineId::new(FileId::new(usize::MAX), 0), false)
-                }
-            };
-            let end_loc_find = self.loader.get_location(bb.end - base);
-            let (end_loc, end_found) = match &end_loc_find {
-                Some(e) => match sut_db.parse_file(
-                    fs::canonicalize(PathBuf::from(e.file.unwrap()))
-                        .unwrap()
-                        .to_str()
-                        .unwrap(),
-                ) {
-                    Some(e_f) => {
-                        let mut ret = (LineId::new(FileId::new(usize::MAX), 0), false);
-                        if e.line.is_some() {
-                            ret = (LineId::new(e_f, e.line.unwrap()), true);
+                };
+                block_lines.push(src_block.lines());
+
+                match src_block {
+                    BlockMapping::Mapped(src_to_insert) => {
+                        if source_trace_set.insert(src_to_insert.clone()) {
+                            for line in src_to_insert.start.num()..=src_to_insert.end.num() {
+                                current_filtered_trace
+                                    .unique_lines_set
+                                    .insert(LineId::new(src_to_insert.start.file(), line));
+                            }
+
+                            current_filtered_trace.binary.push(*bb);
+                            current_filtered_trace.source.push(src_to_insert);
                         }
-                        ret
-                    }
-                    None => {
-                        (LineId::new(FileId::new(usize::MAX), 0), false)
                     }
-                },
+                    BlockMapping::WithoutLine => mapping_stats.blocks_without_line += 1,
+                    BlockMapping::FilteredByPath => mapping_stats.blocks_filtered_by_path += 1,
+                    BlockMapping::SingleLine(_) => {}
+                }
+            }
+            if is_full_trace {
+                count_executions(&block_lines, &mut block_execution_counts);
+                current_filtered_trace
+                    .edges
+                    .extend(consecutive_edges(block_lines));
+            }
+        }
+        drop(block_table);
+        if is_full_trace {
+            for line in current_filtered_trace.unique_lines_set.iter() {
+                if let Some(count) = block_execution_counts.get(line) {
+                    current_filtered_trace.line_hit_counts.insert(*line, *count);
+                }
+            }
+        }
+
+        // a PC covers its line only, so unlike a drcov block it is never dropped for being
+        // on a single line
+        let mut pc_table = self.pc_table.lock().unwrap();
+        for pc in resolved_trace.pcs {
+            let src_block = match pc_table.get(&pc) {
+                Some(src_block) => src_block.clone(),
                 None => {
-                    (LineId::new(FileId::new(usize::MAX), 0), false)
+                    let src_block = self.map_pc(pc, target_config, sut_db)?;
+                    pc_table.insert(pc, src_block.clone());
+                    src_block
                 }
             };
 
-            if source_found && end_found {
-                let source_file_path =
-                    PathBuf::from(&soure_loc_find.unwrap().file.unwrap().to_string());
-                let end_file_path = PathBuf::from(&end_loc_find.unwrap().file.unwrap().to_string());
-                let src_to_insert = SrcCovBasicBlock {
-                    start: source_loc,
-                    end: end_loc,
-                };
-
-                if source_loc != end_loc
-                    && check_ancestors(&source_file_path, target_config)
-                    && check_ancestors(&end_file_path, target_config)
-                    && source_trace_set.insert(src_to_insert.clone())
-                {
-                    for line in src_to_insert.start.num()..=src_to_insert.end.num() {
+            match src_block {
+                BlockMapping::Mapped(src_to_insert) => {
+                    if source_trace_set.insert(src_to_insert.clone()) {
                         current_filtered_trace
                             .unique_lines_set
-                            .insert(LineId::new(src_to_insert.start.file(), line));
+                            .insert(src_to_insert.start);
+                        current_filtered_trace
+                            .binary
+                            .push(DrCovBasicBlock::new(pc, pc));
+                        current_filtered_trace.source.push(src_to_insert);
                     }
+                }
+                BlockMapping::WithoutLine => mapping_stats.blocks_without_line += 1,
+                BlockMapping::FilteredByPath => mapping_stats.blocks_filtered_by_path += 1,
+                BlockMapping::SingleLine(_) => {}
+            }
+        }
+        drop(pc_table);
 
-                    current_filtered_trace.binary.push(bb);
-                    current_filtered_trace.source.push(src_to_insert);
+        for source_file in resolved_trace.source_files {
+            let Some(file_id) = self.map_source_file(&source_file.path, target_config, sut_db)?
+            else {
+                mapping_stats.blocks_filtered_by_path += source_file.regions.len() as u64;
+                continue;
+            };
+            for (line, count) in source_file.line_counts {
+                if count > 0 {
+                    let line_id = LineId::new(file_id, line);
+                    current_filtered_trace.unique_lines_set.insert(line_id);
+                    *current_filtered_trace
+                        .line_hit_counts
+                        .entry(line_id)
+                        .or_default() += count;
+                }
+            }
+            // a line with a taken branch was executed, even if its count is not recorded
+            for (line, taken) in source_file.branches {
+                if taken > 0 {
+                    current_filtered_trace
+                        .unique_lines_set
+                        .insert(LineId::new(file_id, line));
+                }
+            }
+            for (start, end) in source_file.regions {
+                let src_block = SrcCovBasicBlock {
+                    start: LineId::new(file_id, start),
+                    end: LineId::new(file_id, end),
+                };
+                if source_trace_set.insert(src_block.clone()) {
+                    current_filtered_trace.source.push(src_block);
                 }
             }
         }
 
-        current_filtered_trace
+        Ok((current_filtered_trace, mapping_stats))
+    }
+
+    pub fn parse_with_config(
+        &self,
+        trace_file: &str,
+        target_config: &TargetConfig,
+        sut_db: &mut SUT,
+    ) -> Result<Trace, TraceError> {
+        let resolved_trace = self.resolve(trace_file, target_config)?;
+        let (trace, _) = self.map_trace(resolved_trace, target_config, sut_db)?;
+        Ok(trace)
     }
 }
 
@@ -213,15 +694,15 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
-        let trace_map = TraceMap::new(&config.target_path);
+        let trace_map = TraceMap::new(&config.target_path).unwrap();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let parsed_trace = trace_map.parse_with_config(
-            "test_data/traces/drcov_input_b.trace",
-            &config,
-            &mut sut_db,
-        );
+        let parsed_trace = trace_map
+            .parse_with_config("test_data/traces/drcov_input_b.trace", &config, &mut sut_db)
+            .unwrap();
         assert_eq!(parsed_trace.binary.len(), parsed_trace.source.len());
         println!("{:?}", parsed_trace);
         assert_eq!(parsed_trace.binary.len(), 17);
@@ -239,15 +720,15 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
-        let trace_map = TraceMap::new(&config.target_path);
+        let trace_map = TraceMap::new(&config.target_path).unwrap();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let parsed_trace = trace_map.parse_with_config(
-            "test_data/traces/drcov_input_a.trace",
-            &config,
-            &mut sut_db,
-        );
+        let parsed_trace = trace_map
+            .parse_with_config("test_data/traces/drcov_input_a.trace", &config, &mut sut_db)
+            .unwrap();
         assert_eq!(parsed_trace.binary.len(), parsed_trace.source.len());
         println!("{:?}", parsed_trace);
         assert_eq!(parsed_trace.binary.len(), 17);
@@ -266,18 +747,257 @@ mod tests {
             ),
             target_include_filter: vec![],
             allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::DrCov,
+            additional_modules: vec![],
         };
-        let trace_map = TraceMap::new(&config.target_path);
+        let trace_map = TraceMap::new(&config.target_path).unwrap();
         let mut sut_db = SUT::new();
         sut_db.parse_config(&config);
-        let parsed_trace = trace_map.parse_with_config(
-            "test_data/traces/drcov_input_c.trace",
-            &config,
-            &mut sut_db,
-        );
+        let parsed_trace = trace_map
+            .parse_with_config("test_data/traces/drcov_input_c.trace", &config, &mut sut_db)
+            .unwrap();
         assert_eq!(parsed_trace.binary.len(), parsed_trace.source.len());
         println!("{:?}", parsed_trace);
         assert_eq!(parsed_trace.binary.len(), 5);
         assert_eq!(parsed_trace.source.len(), 5);
     }
+
+    #[test]
+    fn test_trace_map_llvm_cov() {
+        let config = TargetConfig {
+            target_path: "test_data/sources/test".to_string(),
+            target_source_code_path: String::from(
+                fs::canonicalize(PathBuf::from("./test_data/sources"))
+                    .unwrap()
+                    .to_str()
+                    .unwrap(),
+            ),
+            target_include_filter: vec![],
+            allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::LlvmCovJson,
+            additional_modules: vec![],
+        };
+        // the target binary is not needed for source based coverage
+        let trace_map = TraceMap::for_target(&config).unwrap();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+        let resolved_trace = trace_map
+            .resolve("test_data/llvm_cov/input_a.json", &config)
+            .unwrap();
+        let (parsed_trace, mapping_stats) = trace_map
+            .map_trace(resolved_trace, &config, &mut sut_db)
+            .unwrap();
+
+        assert!(parsed_trace.binary.is_empty());
+        assert_eq!(parsed_trace.source.len(), 11);
+        assert_eq!(parsed_trace.unique_lines_set.len(), 26);
+        let file_id = sut_db
+            .parse_file(&format!("{}/test.c", config.target_source_code_path))
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed_trace.line_hit_counts[&LineId::new(file_id, 6)], 4);
+        assert!(!parsed_trace
+            .unique_lines_set
+            .contains(&LineId::new(file_id, 8)));
+        // the executed region of the system header
+        assert_eq!(mapping_stats.blocks_filtered_by_path, 1);
+    }
+
+    #[test]
+    fn test_trace_map_gcov() {
+        let config = TargetConfig {
+            target_path: "test_data/sources/test".to_string(),
+            target_source_code_path: String::from(
+                fs::canonicalize(PathBuf::from("./test_data/sources"))
+                    .unwrap()
+                    .to_str()
+                    .unwrap(),
+            ),
+            target_include_filter: vec![],
+            allowed_extensions: vec![],
+            coverage_backend: CoverageBackend::GcovJson,
+            additional_modules: vec![],
+        };
+        let trace_map = TraceMap::for_target(&config).unwrap();
+        let mut sut_db = SUT::new();
+        sut_db.parse_config(&config);
+        let resolved_trace = trace_map
+            .resolve("test_data/gcov/input_a.json", &config)
+            .unwrap();
+        let (parsed_trace, _) = trace_map
+            .map_trace(resolved_trace, &config, &mut sut_db)
+            .unwrap();
+
+        // gcov records no regions, only lines
+        assert!(parsed_trace.source.is_empty());
+        assert_eq!(parsed_trace.unique_lines_set.len(), 21);
+        let file_id = sut_db
+            .parse_file(&format!("{}/test.c", config.target_source_code_path))
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed_trace.line_hit_counts[&LineId::new(file_id, 6)], 4);
+        assert!(!parsed_trace
+            .unique_lines_set
+            .contains(&LineId::new(file_id, 8)));
+        // lines of the system header are filtered like those of any file outside the source path
+        assert!(parsed_trace
+            .unique_lines_set
+            .iter()
+            .all(|line| line.file() == file_id));
+    }
+
+    #[test]
+    fn test_consecutive_edges() {
+        let (file, other_file) = (FileId::new(1), FileId::new(2));
+        let line = |file, num| LineId::new(file, num);
+        let edges = consecutive_edges(vec![
+            Some((line(file, 5), line(file, 6))),
+            // the condition of an if, taken to its else branch
+            Some((line(file, 7), line(file, 7))),
+            Some((line(file, 10), line(file, 11))),
+            // a call into another file and its return
+            Some((line(other_file, 2), line(other_file, 3))),
+            Some((line(file, 12), line(file, 12))),
+            // a block of a library
+            None,
+            Some((line(file, 13), line(file, 14))),
+            Some((line(file, 14), line(file, 15))),
+        ]);
+        assert_eq!(
+            edges,
+            HashSet::from([
+                EdgeId::new(line(file, 6), line(file, 7)),
+                EdgeId::new(line(file, 7), line(file, 10)),
+            ])
+        );
+    }
+
+    /// Maps the blocks of the target module in a drcov trace of `/work/test`, like the qemuafl
+    /// fixture. The binary is not part of the test data, so the blocks at 0x1139, 0x1145 and
+    /// 0x114c are given the lines 10 to 11, 13 to 14 and 16 to 17 up front.
+    fn map_drcov_fixture(trace_file: &str, coverage_backend: CoverageBackend) -> Trace {
+        let config = TargetConfig {
+            target_path: "test".to_string(),
+            coverage_backend,
+            ..TargetConfig::new()
+        };
+        let reader = DrCovReader::read(trace_file).unwrap();
+        let resolved_trace = ResolvedTrace {
+            modules: module_blocks(&reader, trace_file, &config).unwrap(),
+            ..ResolvedTrace::default()
+        };
+        let trace_map = TraceMap {
+            loaders: Vec::new(),
+            block_table: Mutex::new(HashMap::new()),
+            pc_table: Mutex::new(HashMap::new()),
+            file_table: Mutex::new(HashMap::new()),
+        };
+        for (index, (start, end)) in [(0x1139, 0x1145), (0x1145, 0x114c), (0x114c, 0x1151)]
+            .into_iter()
+            .enumerate()
+        {
+            let first_line = 10 + 3 * index as u32;
+            trace_map.block_table.lock().unwrap().insert(
+                (0, start, end),
+                BlockMapping::Mapped(SrcCovBasicBlock {
+                    start: LineId::new(FileId::new(1), first_line),
+                    end: LineId::new(FileId::new(1), first_line + 1),
+                }),
+            );
+        }
+        let (trace, _) = trace_map
+            .map_trace(resolved_trace, &config, &mut SUT::new())
+            .unwrap();
+        trace
+    }
+
+    #[test]
+    fn test_drcov_edges() {
+        let line = |num| LineId::new(FileId::new(1), num);
+        // qemuafl lists every block once, in the order it was translated
+        let trace = map_drcov_fixture("test_data/drcov/qemuafl_v2.trace", CoverageBackend::DrCov);
+        assert_eq!(trace.unique_lines_set.len(), 6);
+        assert!(trace.edges.is_empty());
+
+        // the same blocks as a full trace
+        let trace = map_drcov_fixture(
+            "test_data/drcov/qemuafl_v2.trace",
+            CoverageBackend::DrCovFullTrace,
+        );
+        assert_eq!(trace.unique_lines_set.len(), 6);
+        assert_eq!(
+            trace.edges,
+            HashSet::from([
+                EdgeId::new(line(11), line(13)),
+                EdgeId::new(line(14), line(16)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_drcov_execution_counts() {
+        let line = |num| LineId::new(FileId::new(1), num);
+        // the second block translated again, e.g. after the translation cache was flushed
+        let mut reader = DrCovReader::read("test_data/drcov/qemuafl_v2.trace").unwrap();
+        let retranslated = reader.basic_block_entries[1];
+        reader.basic_block_entries.push(retranslated);
+        let path = std::env::temp_dir().join("seedui_test_drcov_execution_counts.trace");
+        reader.write(&path).unwrap();
+
+        let trace = map_drcov_fixture(&path.to_string_lossy(), CoverageBackend::DrCov);
+        assert_eq!(trace.unique_lines_set.len(), 6);
+        assert!(trace.line_hit_counts.is_empty());
+        assert!(trace.edges.is_empty());
+
+        // as a full trace, the second block was executed twice
+        let trace = map_drcov_fixture(&path.to_string_lossy(), CoverageBackend::DrCovFullTrace);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(trace.line_hit_counts[&line(10)], 1);
+        assert_eq!(trace.line_hit_counts[&line(13)], 2);
+        assert_eq!(trace.line_hit_counts[&line(14)], 2);
+        assert_eq!(trace.line_hit_counts[&line(17)], 1);
+        assert!(trace.edges.contains(&EdgeId::new(line(17), line(13))));
+    }
+
+    #[test]
+    fn test_count_executions() {
+        let file = FileId::new(1);
+        let line = |num| LineId::new(file, num);
+        let mut counts = HashMap::new();
+        // a loop over lines 6 to 7 taken twice, then the block after it
+        count_executions(
+            &[
+                Some((line(5), line(6))),
+                Some((line(6), line(7))),
+                Some((line(6), line(7))),
+                None,
+                Some((line(9), line(9))),
+            ],
+            &mut counts,
+        );
+        assert_eq!(
+            counts,
+            HashMap::from([(line(5), 1), (line(6), 3), (line(7), 2), (line(9), 1)])
+        );
+    }
+
+    #[test]
+    fn test_module_blocks_relative() {
+        // PIE binaries and shared objects are linked at 0
+        let shared_object = ModuleBlocks {
+            module: 1,
+            base: 0x7fff_f7fc_3000,
+            preferred_base: 0,
+            blocks: vec![],
+        };
+        assert_eq!(shared_object.relative(0x7fff_f7fe_3290), 0x2_0290);
+        // the DWARF addresses of non PIE binaries start at their preferred base
+        let non_pie = ModuleBlocks {
+            module: 0,
+            base: 0x40_0000,
+            preferred_base: 0x40_0000,
+            blocks: vec![],
+        };
+        assert_eq!(non_pie.relative(0x40_1136), 0x40_1136);
+    }
 }
@@ -0,0 +1,251 @@
+//! Reader for the JSON written by `llvm-cov export -format=text`, see
+//! https://github.com/llvm/llvm-project/blob/main/llvm/tools/llvm-cov/CoverageExporterJson.cpp
+
+use std::{collections::BTreeMap, fs::File, io::BufReader, path::Path};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{source_coverage::SourceFileCoverage, TraceError};
+
+const EXPORT_TYPE: &str = "llvm.coverage.json.export";
+// kind of a region that is plain code, i.e. no expansion, skipped or branch region
+const CODE_REGION: u64 = 0;
+
+#[derive(Deserialize)]
+struct Export {
+    #[serde(rename = "type")]
+    export_type: String,
+    data: Vec<ExportData>,
+}
+
+#[derive(Deserialize)]
+struct ExportData {
+    files: Vec<ExportFile>,
+    // missing with -skip-functions
+    #[serde(default)]
+    functions: Vec<ExportFunction>,
+}
+
+#[derive(Deserialize)]
+struct ExportFile {
+    filename: String,
+    #[serde(default)]
+    segments: Vec<Vec<Value>>,
+    // [line start, column start, line end, column end, true count, false count, file id,
+    // expanded file id, kind], missing in exports of older LLVM versions
+    #[serde(default)]
+    branches: Vec<Vec<u64>>,
+}
+
+#[derive(Deserialize)]
+struct ExportFunction {
+    // [line start, column start, line end, column end, execution count, file id,
+    //  expanded file id, kind]
+    regions: Vec<Vec<u64>>,
+    filenames: Vec<String>,
+}
+
+/// A coverage segment, where the state of the regions changes:
+/// [line, column, count, has count, is region entry, is gap region]. The gap flag is missing in
+/// exports of older LLVM versions.
+struct Segment {
+    line: u32,
+    count: u64,
+    has_count: bool,
+    is_region_entry: bool,
+    is_gap_region: bool,
+}
+
+impl Segment {
+    fn from_json(values: &[Value]) -> Option<Segment> {
+        let flag = |index: usize| values.get(index).and_then(Value::as_bool);
+        Some(Segment {
+            line: u32::try_from(values.first()?.as_u64()?).ok()?,
+            count: values.get(2)?.as_u64()?,
+            has_count: flag(3)?,
+            is_region_entry: flag(4)?,
+            is_gap_region: flag(5).unwrap_or(false),
+        })
+    }
+
+    fn is_start_of_region(&self) -> bool {
+        !self.is_gap_region && self.has_count && self.is_region_entry
+    }
+}
+
+/// Execution count of every instrumented line, computed from the segments of a file the same way
+/// `llvm-cov show` does (`LineCoverageStats` in llvm/ProfileData/Coverage/CoverageMapping.cpp).
+fn line_counts(segments: &[Segment]) -> Vec<(u32, u64)> {
+    let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+        return Vec::new();
+    };
+
+    let mut line_counts = Vec::new();
+    let mut wrapped_segment: Option<&Segment> = None;
+    let mut next_segment = 0;
+    for line in first.line..=last.line {
+        let line_start = next_segment;
+        while segments
+            .get(next_segment)
+            .is_some_and(|segment| segment.line == line)
+        {
+            next_segment += 1;
+        }
+        let line_segments = &segments[line_start..next_segment];
+
+        let region_starts: Vec<&Segment> = line_segments
+            .iter()
+            .filter(|segment| segment.is_start_of_region())
+            .collect();
+        let start_of_skipped_region = line_segments
+            .first()
+            .is_some_and(|segment| !segment.has_count && segment.is_region_entry);
+        let is_mapped = !start_of_skipped_region
+            && (wrapped_segment.is_some_and(|segment| segment.has_count)
+                || !region_starts.is_empty());
+        if is_mapped {
+            let wrapped_count = wrapped_segment.map_or(0, |segment| segment.count);
+            let count = region_starts
+                .iter()
+                .map(|segment| segment.count)
+                .fold(wrapped_count, u64::max);
+            line_counts.push((line, count));
+        }
+
+        if let Some(segment) = line_segments.last() {
+            wrapped_segment = Some(segment);
+        }
+    }
+
+    line_counts
+}
+
+/// Reads the coverage of every source file in the `llvm-cov export` of one input
+pub fn read_llvm_cov_export(path: &str) -> Result<Vec<SourceFileCoverage>, TraceError> {
+    let format_error = |reason: String| TraceError::LlvmCov {
+        path: path.to_string(),
+        reason,
+    };
+
+    let file = File::open(Path::new(path)).map_err(|source| TraceError::Io {
+        path: path.to_string(),
+        source,
+    })?;
+    let export: Export =
+        serde_json::from_reader(BufReader::new(file)).map_err(|e| format_error(e.to_string()))?;
+    if export.export_type != EXPORT_TYPE {
+        return Err(format_error(format!(
+            "expected type {} but got {}",
+            EXPORT_TYPE, export.export_type
+        )));
+    }
+
+    // an export may hold several objects, e.g. a binary and its shared libraries
+    let mut files: BTreeMap<String, SourceFileCoverage> = BTreeMap::new();
+    for data in export.data {
+        for export_file in data.files {
+            let segments: Vec<Segment> = export_file
+                .segments
+                .iter()
+                .map(|values| Segment::from_json(values))
+                .collect::<Option<_>>()
+                .ok_or_else(|| {
+                    format_error(format!("invalid segment in {}", export_file.filename))
+                })?;
+            let file = files
+                .entry(export_file.filename.clone())
+                .or_insert_with(|| SourceFileCoverage {
+                    path: export_file.filename,
+                    ..SourceFileCoverage::default()
+                });
+            file.line_counts.extend(line_counts(&segments));
+            for branch in export_file.branches {
+                let &[line_start, _, _, _, true_count, false_count, ..] = branch.as_slice() else {
+                    return Err(format_error(format!("invalid branch {:?}", branch)));
+                };
+                file.branches.push((line_start as u32, true_count));
+                file.branches.push((line_start as u32, false_count));
+            }
+        }
+
+        for function in data.functions {
+            for region in function.regions {
+                let &[line_start, _, line_end, _, count, file_id, _, kind, ..] = region.as_slice()
+                else {
+                    return Err(format_error(format!("invalid region {:?}", region)));
+                };
+                if kind != CODE_REGION || count == 0 {
+                    continue;
+                }
+                let Some(filename) = function.filenames.get(file_id as usize) else {
+                    return Err(format_error(format!(
+                        "invalid file id in region {:?}",
+                        region
+                    )));
+                };
+                files
+                    .entry(filename.clone())
+                    .or_insert_with(|| SourceFileCoverage {
+                        path: filename.clone(),
+                        ..SourceFileCoverage::default()
+                    })
+                    .regions
+                    .push((line_start as u32, line_end as u32));
+            }
+        }
+    }
+
+    Ok(files.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_llvm_cov_export() {
+        let files = read_llvm_cov_export("test_data/llvm_cov/input_a.json").unwrap();
+        assert_eq!(files.len(), 2);
+
+        let test_c = files.iter().find(|f| f.path == "test.c").unwrap();
+        let count_of = |line: u32| {
+            test_c
+                .line_counts
+                .iter()
+                .find(|(l, _)| *l == line)
+                .map(|(_, count)| *count)
+        };
+        // the declaration of foo is a region of its own
+        assert_eq!(count_of(3), Some(2));
+        assert_eq!(count_of(4), None);
+        // the loop condition is evaluated once more than the body
+        assert_eq!(count_of(6), Some(4));
+        assert_eq!(count_of(7), Some(3));
+        // the branch not taken and the closing brace of its region
+        assert_eq!(count_of(8), Some(0));
+        assert_eq!(count_of(10), Some(0));
+        assert_eq!(count_of(11), Some(3));
+        assert_eq!(count_of(21), None);
+        assert_eq!(count_of(36), Some(1));
+        assert_eq!(
+            test_c.line_counts.iter().filter(|(_, c)| *c > 0).count(),
+            26
+        );
+        assert_eq!(test_c.regions.len(), 11);
+        assert_eq!(test_c.branches.len(), 8);
+        assert!(test_c.branches.contains(&(6, 3)));
+    }
+
+    #[test]
+    fn test_read_llvm_cov_export_invalid() {
+        assert!(matches!(
+            read_llvm_cov_export("test_data/llvm_cov/does_not_exist.json"),
+            Err(TraceError::Io { .. })
+        ));
+        assert!(matches!(
+            read_llvm_cov_export("test_data/drcov/qemuafl_v2.trace"),
+            Err(TraceError::LlvmCov { .. })
+        ));
+    }
+}
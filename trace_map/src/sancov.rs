@@ -0,0 +1,86 @@
+//! Reader for the PC lists written by SanitizerCoverage on exit, e.g. with
+//! `ASAN_OPTIONS=coverage=1`, see https://clang.llvm.org/docs/SanitizerCoverage.html
+
+use std::fs;
+
+use crate::TraceError;
+
+const MAGIC_64: u64 = 0xC0BF_FFFF_FFFF_FF64;
+const MAGIC_32: u64 = 0xC0BF_FFFF_FFFF_FF32;
+
+/// Reads the PCs of the covered edges in the `.sancov` file of one input, as offsets into the
+/// module. The magic tells the width of the PCs, the byte order is the one of the target.
+pub fn read_sancov(path: &str) -> Result<Vec<u64>, TraceError> {
+    let bytes = fs::read(path).map_err(|source| TraceError::Io {
+        path: path.to_string(),
+        source,
+    })?;
+    let format_error = |reason: &str| TraceError::Sancov {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let Some((magic, pcs)) = bytes.split_first_chunk::<8>() else {
+        return Err(format_error("missing magic"));
+    };
+    let (width, from_bytes): (usize, fn(&[u8]) -> u64) = match *magic {
+        m if u64::from_le_bytes(m) == MAGIC_64 => {
+            (8, |b| u64::from_le_bytes(b.try_into().unwrap()))
+        }
+        m if u64::from_be_bytes(m) == MAGIC_64 => {
+            (8, |b| u64::from_be_bytes(b.try_into().unwrap()))
+        }
+        m if u64::from_le_bytes(m) == MAGIC_32 => {
+            (4, |b| u32::from_le_bytes(b.try_into().unwrap()) as u64)
+        }
+        m if u64::from_be_bytes(m) == MAGIC_32 => {
+            (4, |b| u32::from_be_bytes(b.try_into().unwrap()) as u64)
+        }
+        _ => return Err(format_error("unknown magic")),
+    };
+    if pcs.len() % width != 0 {
+        return Err(format_error("truncated PC"));
+    }
+
+    Ok(pcs.chunks_exact(width).map(from_bytes).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_sancov() {
+        let pcs = read_sancov("test_data/sancov/input_a.sancov").unwrap();
+        assert_eq!(pcs.len(), 9);
+        assert_eq!(pcs[0], 0x1189);
+    }
+
+    #[test]
+    fn test_read_sancov_32() {
+        let path = std::env::temp_dir().join("seedui_test_sancov_32.sancov");
+        let mut bytes = MAGIC_32.to_be_bytes().to_vec();
+        bytes.extend(0x0804_9186u32.to_be_bytes());
+        bytes.extend(0x0804_91a2u32.to_be_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(
+            read_sancov(&path.to_string_lossy()).unwrap(),
+            vec![0x0804_9186, 0x0804_91a2]
+        );
+
+        bytes.pop();
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            read_sancov(&path.to_string_lossy()),
+            Err(TraceError::Sancov { .. })
+        ));
+        std::fs::write(&path, MAGIC_32.to_le_bytes()).unwrap();
+        assert!(read_sancov(&path.to_string_lossy()).unwrap().is_empty());
+        std::fs::write(&path, "DRCOV VERSION: 2\n").unwrap();
+        assert!(matches!(
+            read_sancov(&path.to_string_lossy()),
+            Err(TraceError::Sancov { .. })
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,14 @@
+/// Coverage of one source file as recorded by a source based coverage backend, before it is
+/// mapped onto the `SUT`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceFileCoverage {
+    /// Path of the source file as recorded at compile time, may be relative to the compilation
+    /// directory
+    pub path: String,
+    /// (line, execution count) of every instrumented line, executed or not
+    pub line_counts: Vec<(u32, u64)>,
+    /// (start line, end line) of every executed code region
+    pub regions: Vec<(u32, u32)>,
+    /// (line, times taken) of every outcome of every branch
+    pub branches: Vec<(u32, u64)>,
+}
@@ -1,26 +1,77 @@
-use addr2line::{Loader, Location};
-use std::path::PathBuf;
+use addr2line::Loader;
+use std::{
+    collections::HashMap,
+    error::Error,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+};
 
+/// Source location of an address in the target binary
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: Arc<str>,
+    pub line: Option<u32>,
+}
+
+/// Address to source location resolver for one target binary.
+///
+/// The DWARF information is loaded once and every resolved address is memoized, so the resolver
+/// can be shared between all traces (and threads) of a target.
 pub struct TraceLoader {
-    target_loader: Loader,
+    binary: PathBuf,
+    // loaders not in use right now. A lookup that misses the cache takes one, or loads another if
+    // all are taken, so the workers resolving traces symbolize in parallel. There are never more
+    // than workers resolving at the same time.
+    idle_loaders: Mutex<Vec<Loader>>,
+    location_cache: RwLock<HashMap<u64, Option<SourceLocation>>>,
 }
 
 impl TraceLoader {
-    pub fn new(binary: &str) -> TraceLoader {
-        TraceLoader {
-            target_loader: Loader::new(PathBuf::from(binary)).unwrap(),
-        }
+    pub fn new(binary: &str) -> Result<TraceLoader, Box<dyn Error>> {
+        let binary = PathBuf::from(binary);
+        Ok(TraceLoader {
+            idle_loaders: Mutex::new(vec![Loader::new(&binary)?]),
+            binary,
+            location_cache: RwLock::new(HashMap::new()),
+        })
     }
 
-    pub fn get_location(&self, hex: u64) -> Option<Location<'_>> {
-        // Caching possible here!
-        match self.target_loader.find_location(hex) {
-            Ok(e) => e,
+    pub fn get_location(&self, hex: u64) -> Option<SourceLocation> {
+        if let Some(location) = self.location_cache.read().unwrap().get(&hex) {
+            return location.clone();
+        }
+
+        let idle_loader = self.idle_loaders.lock().unwrap().pop();
+        let loader = match idle_loader {
+            Some(loader) => loader,
+            None => match Loader::new(&self.binary) {
+                Ok(loader) => loader,
+                Err(e) => {
+                    // loaded fine before, so the binary changed, e.g. it is being rebuilt
+                    log::error!("unable to load {:?} again: {}", self.binary, e);
+                    return None;
+                }
+            },
+        };
+        let location = match loader.find_location(hex) {
+            Ok(e) => e.and_then(|l| {
+                Some(SourceLocation {
+                    file: Arc::from(l.file?),
+                    line: l.line,
+                })
+            }),
             Err(e) => {
-                println!("Location {:#x} with error: {:?}", hex, e);
+                log::warn!("unable to resolve {:#x}: {:?}", hex, e);
                 None
             }
-        }
+        };
+        self.idle_loaders.lock().unwrap().push(loader);
+
+        self.location_cache
+            .write()
+            .unwrap()
+            .insert(hex, location.clone());
+        location
     }
 }
 
@@ -31,7 +82,7 @@ mod tests {
     #[ignore]
     #[test]
     fn it_works() {
-        let trace_info = TraceLoader::new("test_data/sources/test");
+        let trace_info = TraceLoader::new("test_data/sources/test").unwrap();
         assert_eq!(
             trace_info
                 // virtual address - base
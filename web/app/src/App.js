@@ -4,7 +4,8 @@ import Divider from '@mui/material/Divider';
 import Chip from '@mui/material/Chip';
 
 import { useState, useEffect } from 'react';
-import { getFuzzerInfo } from './components/fetchers.js'
+import { useSWRConfig } from 'swr';
+import { getFuzzerInfo, getReloadStatus } from './components/fetchers.js'
 import FuzzerInfo from './components/FuzzerDetails';
 import SUTStatistics from './components/SUTStatistics.js';
 import Overview from './components/Overview.js';
@@ -17,6 +18,16 @@ function App() {
   const [fuzzersInfo, setFuzzersInfo] = useState(new Map());
 
   let { data: fuzzer_data, isLoading } = getFuzzerInfo();
+  let { data: reload_status } = getReloadStatus();
+  const generation = reload_status?.generation;
+  const { mutate } = useSWRConfig();
+  useEffect(() => {
+    // the server ingested new traces, everything fetched so far may be outdated
+    if (generation > 0) {
+      mutate((key) => key !== "/reload_status");
+    }
+  }, [mutate, generation]);
+
   useEffect(() => {
     if (isLoading !== undefined && !isLoading) {  
       const defaultColors = d3.schemeCategory10;
@@ -31,7 +42,15 @@ function App() {
         fuzzersInfoMap.set(element.fuzzer_configuration_id, FInfo);
         colorIndex += 1;
       });
-      setFuzzersInfo(fuzzersInfoMap);
+      // keep the fuzzers the user deselected when new inputs arrive
+      setFuzzersInfo(previousFuzzersInfo => {
+        fuzzersInfoMap.forEach((FInfo, fuzzer_configuration_id) => {
+          if (previousFuzzersInfo.has(fuzzer_configuration_id)) {
+            FInfo.checked = previousFuzzersInfo.get(fuzzer_configuration_id).checked;
+          }
+        });
+        return fuzzersInfoMap;
+      });
     }
   }, [setFuzzersInfo, isLoading, fuzzer_data]);
   
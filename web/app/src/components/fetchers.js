@@ -3,14 +3,54 @@ import useSWRImmtable from "swr/immutable";
 import axios from 'axios';
 
 const instance = axios.create({
-    baseURL: "http://127.0.0.1:8080/",
+    baseURL: process.env.NEXT_PUBLIC_SEEDUI_SERVER || "http://127.0.0.1:8080/",
     timeout: 40000,
     headers: {
         Accept: 'application/json, text/plain, */*',
-        'Content-Type': 'application/json; charset=utf-8'
+        'Content-Type': 'application/json; charset=utf-8',
     }
 });
 
+// the server only requires credentials if its configuration has an `auth` section, they are asked
+// for once a request is refused and kept for the browser tab only, never built into the client
+const AUTHORIZATION_KEY = "seedui_authorization";
+
+function askForAuthorization(challenge) {
+    if (challenge.startsWith("Basic")) {
+        const username = window.prompt("Username for the SeedUI server");
+        const password = username && window.prompt("Password for the SeedUI server");
+        return password && `Basic ${window.btoa(`${username}:${password}`)}`;
+    }
+    const token = window.prompt("Token of the SeedUI server");
+    return token && `Bearer ${token}`;
+}
+
+instance.interceptors.request.use((config) => {
+    const authorization = window.sessionStorage.getItem(AUTHORIZATION_KEY);
+    if (authorization) {
+        config.headers.Authorization = authorization;
+    }
+    return config;
+});
+
+instance.interceptors.response.use(undefined, (error) => {
+    const config = error.config;
+    if (error.response?.status !== 401 || !config || config.retriedWithAuthorization) {
+        return Promise.reject(error);
+    }
+
+    let authorization = window.sessionStorage.getItem(AUTHORIZATION_KEY);
+    // another refused request may have asked for the credentials in the meantime
+    if (!authorization || config.headers.Authorization === authorization) {
+        authorization = askForAuthorization(error.response.headers["www-authenticate"] ?? "");
+        if (!authorization) {
+            return Promise.reject(error);
+        }
+        window.sessionStorage.setItem(AUTHORIZATION_KEY, authorization);
+    }
+    return instance({ ...config, retriedWithAuthorization: true });
+});
+
 
 const get_fetcher = (url) => {
     return instance.get(url).then((res) => {
@@ -63,6 +103,20 @@ async function postInputClusters(post_data) {
         .then(response => response.data);
 }
 
+function getReloadStatus() {
+    // eslint-disable-next-line react-hooks/rules-of-hooks
+    const { data, error, isLoading } = useSWR("/reload_status", get_fetcher, {
+        // only servers watching the traces directories ever change their generation
+        refreshInterval: (latestData) => latestData?.watch_interval_ms ?? 0
+    });
+
+    return {
+        data,
+        isLoading,
+        error
+    }
+}
+
 function getSUT() {
     // eslint-disable-next-line react-hooks/rules-of-hooks
     const { data, error, isLoading } = useSWR("/sut", get_fetcher);
@@ -85,4 +139,4 @@ function getSUTFileInfoMap() {
     }
 }
 
-export { postLineCoverageOvertime, postCompareInputs, postInitialSeedsCoverage, postInitialSeedTimeline, postInitialSeedsChildCoverage, getSUT, getSUTFileInfoMap, getFuzzerInfo, postInputClusters };
\ No newline at end of file
+export { getReloadStatus, postLineCoverageOvertime, postCompareInputs, postInitialSeedsCoverage, postInitialSeedTimeline, postInitialSeedsChildCoverage, getSUT, getSUTFileInfoMap, getFuzzerInfo, postInputClusters };
\ No newline at end of file